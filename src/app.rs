@@ -0,0 +1,280 @@
+use crate::{
+    collector::Snapshot,
+    config::{Config, ConfigError},
+    disk::DiskFilter,
+    format::RateUnit,
+    history::{History, Window},
+    layout::{self, Node, Tab},
+    network::{self, InterfaceFilter, PeakScale, Throughput},
+    planner::Source,
+    process::ProcessTable,
+    sensors,
+    signal::{PendingSignal, SignalMenu, Target},
+};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+    time::Instant,
+};
+
+pub enum Mode {
+    Normal,
+    SignalMenu(SignalMenu),
+    Confirm(PendingSignal),
+}
+
+pub struct App {
+    pub config: Config,
+    pub tabs: Vec<Tab>,
+    pub tab: usize,
+    pub layout: Node,
+    pub snapshot: Arc<Snapshot>,
+    pub applied_at: Instant,
+    pub rate_unit: RateUnit,
+    pub net_filter: InterfaceFilter,
+    pub throughput: Throughput,
+    pub disk_filter: DiskFilter,
+    pub download_peak: PeakScale,
+    pub upload_peak: PeakScale,
+    pub cpu_history: History,
+    pub memory_history: History,
+    pub swap_history: History,
+    pub rx_history: History,
+    pub tx_history: History,
+    pub disk_read_history: History,
+    pub disk_write_history: History,
+    pub sensor_history: HashMap<String, History>,
+    pub window: Window,
+    pub processes: ProcessTable,
+    pub mode: Mode,
+    pub status: Option<String>,
+}
+
+impl App {
+    pub fn new(config: Config) -> Self {
+        let tabs = config.layout.tabs();
+        Self {
+            layout: resolve_layout(&config, &tabs[0].layout),
+            tabs,
+            tab: 0,
+            snapshot: Arc::new(Snapshot::default()),
+            applied_at: Instant::now(),
+            rate_unit: config.units.rate,
+            net_filter: config.network.clone(),
+            throughput: Throughput::default(),
+            disk_filter: config.disks.clone(),
+            download_peak: PeakScale::default(),
+            upload_peak: PeakScale::default(),
+            cpu_history: History::default(),
+            memory_history: History::default(),
+            swap_history: History::default(),
+            rx_history: History::default(),
+            tx_history: History::default(),
+            disk_read_history: History::default(),
+            disk_write_history: History::default(),
+            sensor_history: HashMap::new(),
+            window: config.units.history_window,
+            processes: ProcessTable::new(),
+            mode: Mode::Normal,
+            status: None,
+            config,
+        }
+    }
+
+    pub fn apply(&mut self, snapshot: Arc<Snapshot>) {
+        let now = Instant::now();
+        let elapsed = now.duration_since(self.applied_at);
+        self.applied_at = now;
+        self.throughput = network::aggregate(&snapshot.interfaces, &self.net_filter);
+        self.download_peak.update(self.throughput.rx, elapsed);
+        self.upload_peak.update(self.throughput.tx, elapsed);
+        self.cpu_history.push(now, snapshot.cpu_usage as f64);
+        if let Some(percent) = snapshot.memory.used_percent() {
+            self.memory_history.push(now, percent);
+        }
+        if let Some(percent) = snapshot.memory.swap_percent() {
+            self.swap_history.push(now, percent);
+        }
+        self.rx_history.push(now, self.throughput.rx);
+        self.tx_history.push(now, self.throughput.tx);
+        self.disk_read_history
+            .push(now, snapshot.disk_io.iter().map(|d| d.read_rate).sum());
+        self.disk_write_history
+            .push(now, snapshot.disk_io.iter().map(|d| d.write_rate).sum());
+        sensors::record(&mut self.sensor_history, &snapshot.sensors, now);
+        self.processes.update(snapshot.processes.clone());
+        self.snapshot = snapshot;
+    }
+
+    pub fn reload(&mut self, result: Result<Config, ConfigError>) {
+        let config = match result {
+            Ok(config) => config,
+            Err(err) => {
+                self.status = Some(format!("Config not applied: {}", err));
+                return;
+            }
+        };
+        if config.units.rate != self.config.units.rate {
+            self.rate_unit = config.units.rate;
+        }
+        if config.units.history_window != self.config.units.history_window {
+            self.window = config.units.history_window;
+        }
+        if config.network != self.config.network {
+            self.net_filter = config.network.clone();
+        }
+        if config.disks != self.config.disks {
+            self.disk_filter = config.disks.clone();
+        }
+        let previous = self.config.layout.tabs();
+        let mut tabs = config.layout.tabs();
+        for tab in &mut tabs {
+            let unchanged = previous.contains(tab);
+            if let Some(current) = self.tabs.iter().find(|t| t.title == tab.title)
+                && unchanged
+                && config.layout.resolve(&current.layout).is_some()
+            {
+                tab.layout = current.layout.clone();
+            }
+        }
+        let active = &self.tabs[self.tab].title;
+        self.tab = tabs.iter().position(|t| t.title == *active).unwrap_or(0);
+        self.tabs = tabs;
+        self.layout = resolve_layout(&config, &self.tabs[self.tab].layout);
+        self.config = config;
+        self.status = Some("Config reloaded".to_string());
+    }
+
+    pub fn visible_sources(&self) -> HashSet<Source> {
+        self.layout
+            .panels()
+            .into_iter()
+            .flat_map(|panel| panel.sources().iter().copied())
+            .collect()
+    }
+
+    fn select_tab(&mut self, index: usize) {
+        if let Some(tab) = self.tabs.get(index) {
+            self.tab = index;
+            self.layout = resolve_layout(&self.config, &tab.layout);
+        }
+    }
+
+    fn cycle_layout(&mut self) {
+        let names = self.config.layout.names();
+        let tab = &mut self.tabs[self.tab];
+        let next = names
+            .iter()
+            .position(|name| *name == tab.layout)
+            .map(|i| (i + 1) % names.len())
+            .unwrap_or(0);
+        tab.layout = names[next].clone();
+        self.layout = resolve_layout(&self.config, &tab.layout);
+        self.status = Some(format!("Layout: {}", tab.layout));
+    }
+
+    fn selected_target(&self, subtree: bool) -> Option<Target> {
+        let p = self.processes.selected()?;
+        if subtree {
+            let pids = self.processes.selected_subtree();
+            Some(Target {
+                label: format!("{} ({}) and {} descendants", p.pid, p.name, pids.len() - 1),
+                pids,
+            })
+        } else {
+            Some(Target {
+                pids: vec![p.pid],
+                label: format!("{} ({})", p.pid, p.name),
+            })
+        }
+    }
+
+    pub fn on_key(&mut self, key: KeyEvent) -> bool {
+        if key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL) {
+            return true;
+        }
+        match std::mem::replace(&mut self.mode, Mode::Normal) {
+            Mode::Normal => return self.on_normal_key(key),
+            Mode::SignalMenu(mut menu) => match key.code {
+                KeyCode::Esc => {}
+                KeyCode::Up => {
+                    menu.move_selection(-1);
+                    self.mode = Mode::SignalMenu(menu);
+                }
+                KeyCode::Down => {
+                    menu.move_selection(1);
+                    self.mode = Mode::SignalMenu(menu);
+                }
+                KeyCode::Backspace => {
+                    menu.custom.pop();
+                    self.mode = Mode::SignalMenu(menu);
+                }
+                KeyCode::Char(c) if c.is_ascii_digit() => {
+                    menu.push_digit(c);
+                    self.mode = Mode::SignalMenu(menu);
+                }
+                KeyCode::Enter => match menu.signal() {
+                    Some(signal) => {
+                        self.mode = Mode::Confirm(PendingSignal {
+                            target: menu.target,
+                            signal,
+                        })
+                    }
+                    None => {
+                        self.status = Some(format!("Invalid signal number: {}", menu.custom));
+                        self.mode = Mode::SignalMenu(menu);
+                    }
+                },
+                _ => self.mode = Mode::SignalMenu(menu),
+            },
+            Mode::Confirm(pending) => match key.code {
+                KeyCode::Char('y') | KeyCode::Enter => self.status = Some(pending.send()),
+                KeyCode::Char('n') | KeyCode::Esc => {}
+                _ => self.mode = Mode::Confirm(pending),
+            },
+        }
+        false
+    }
+
+    fn on_normal_key(&mut self, key: KeyEvent) -> bool {
+        match key.code {
+            KeyCode::Char('q') => return true,
+            KeyCode::Up => self.processes.move_selection(-1),
+            KeyCode::Down => self.processes.move_selection(1),
+            KeyCode::PageUp => self.processes.page_up(),
+            KeyCode::PageDown => self.processes.page_down(),
+            KeyCode::Home => self.processes.first(),
+            KeyCode::End => self.processes.last(),
+            KeyCode::Char('<') => self.processes.set_sort(self.processes.sort.prev()),
+            KeyCode::Char('>') => self.processes.set_sort(self.processes.sort.next()),
+            KeyCode::Char('r') => self.processes.reverse(),
+            KeyCode::Char('t') => self.processes.toggle_tree(),
+            KeyCode::Char('w') => self.window = self.window.next(),
+            KeyCode::Char('b') => self.rate_unit = self.rate_unit.toggle(),
+            KeyCode::Char('l') => self.cycle_layout(),
+            KeyCode::Tab => self.select_tab((self.tab + 1) % self.tabs.len()),
+            KeyCode::BackTab => self.select_tab((self.tab + self.tabs.len() - 1) % self.tabs.len()),
+            KeyCode::Char(c @ '1'..='9') => self.select_tab(c as usize - '1' as usize),
+            KeyCode::Char('f') => self.disk_filter.show_pseudo = !self.disk_filter.show_pseudo,
+            KeyCode::Char('v') => {
+                self.net_filter.hide_virtual = !self.net_filter.hide_virtual;
+                self.throughput = network::aggregate(&self.snapshot.interfaces, &self.net_filter);
+            }
+            KeyCode::Left => self.processes.set_collapsed(Some(true)),
+            KeyCode::Right => self.processes.set_collapsed(Some(false)),
+            KeyCode::Char(' ') => self.processes.set_collapsed(None),
+            KeyCode::Char(c @ ('k' | 'K')) => {
+                if let Some(target) = self.selected_target(c == 'K') {
+                    self.mode = Mode::SignalMenu(SignalMenu::new(target));
+                }
+            }
+            _ => {}
+        }
+        false
+    }
+}
+
+fn resolve_layout(config: &Config, name: &str) -> Node {
+    config.layout.resolve(name).unwrap_or_else(layout::full)
+}
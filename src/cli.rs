@@ -0,0 +1,281 @@
+use crate::{
+    config::{Config, Millis},
+    format::RateUnit,
+};
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use std::{fmt, io::IsTerminal, net::SocketAddr, path::PathBuf, time::Duration};
+
+/// Terminal system monitor
+#[derive(Debug, Parser)]
+#[command(name = "systemcli", version)]
+pub struct Cli {
+    /// Base refresh interval in milliseconds
+    #[arg(short, long, global = true, value_name = "MS", value_parser = parse_interval)]
+    pub interval: Option<Duration>,
+
+    /// Config file to use instead of $XDG_CONFIG_HOME/systemcli/config.toml
+    #[arg(short, long, global = true, value_name = "PATH")]
+    pub config: Option<PathBuf>,
+
+    /// Unit for network and disk rates
+    #[arg(short, long, global = true, value_enum)]
+    pub units: Option<RateUnit>,
+
+    /// When to use colours
+    #[arg(long, global = true, value_enum, default_value_t = ColorMode::Auto)]
+    pub color: ColorMode,
+
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Full-screen interactive monitor (default)
+    Top,
+    /// Print a single sample and exit
+    Snapshot(SnapshotArgs),
+    /// Print selected metrics every interval
+    Watch(WatchArgs),
+    /// Push metrics to an external time-series database
+    Export(ExportArgs),
+    /// Serve metrics over HTTP
+    Serve(ServeArgs),
+}
+
+#[derive(Debug, Args)]
+pub struct SnapshotArgs {
+    /// Output format
+    #[arg(short, long, value_enum, default_value_t = OutputFormat::Json)]
+    pub format: OutputFormat,
+
+    /// Time between the two samples used for rates [default: refresh interval]
+    #[arg(long, value_name = "MS", value_parser = parse_interval)]
+    pub delay: Option<Duration>,
+
+    /// Number of processes to include, busiest first
+    #[arg(long, value_name = "N", default_value_t = 10)]
+    pub top: usize,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    Json,
+    Yaml,
+    Table,
+}
+
+#[derive(Debug, Args)]
+pub struct WatchArgs {
+    /// Metrics to print, e.g. cpu, mem, net.rx, net.eth0.rx or cpu.* [default: all]
+    pub metrics: Vec<String>,
+
+    /// Comma-separated metrics, added to any given as arguments
+    #[arg(long, value_name = "LIST", value_delimiter = ',')]
+    pub fields: Vec<String>,
+
+    /// Output format
+    #[arg(short, long, value_enum, default_value_t = WatchFormat::Text)]
+    pub format: WatchFormat,
+
+    /// Stop after this many samples
+    #[arg(short = 'n', long, value_name = "N", value_parser = clap::value_parser!(u64).range(1..))]
+    pub count: Option<u64>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum WatchFormat {
+    Text,
+    Ndjson,
+}
+
+#[derive(Debug, Args)]
+#[command(group = clap::ArgGroup::new("target").required(true).multiple(true))]
+pub struct ExportArgs {
+    /// InfluxDB line protocol endpoint, e.g. http://localhost:8086/write?db=metrics or udp://localhost:8089
+    #[arg(long, value_name = "URL", group = "target", value_parser = parse_influx)]
+    pub influx: Option<Target>,
+
+    /// Token sent as `Authorization: Token ...` to InfluxDB over HTTP
+    #[arg(
+        long,
+        value_name = "TOKEN",
+        env = "INFLUX_TOKEN",
+        hide_env_values = true
+    )]
+    pub influx_token: Option<String>,
+
+    /// Graphite plaintext endpoint, e.g. localhost:2003
+    #[arg(long, value_name = "ADDR", group = "target", value_parser = parse_graphite)]
+    pub graphite: Option<Target>,
+
+    /// Prefix for measurement and metric names
+    #[arg(long, value_name = "PREFIX", default_value = "systemcli")]
+    pub prefix: String,
+
+    /// Tag added to every point, e.g. --tag dc=eu1; host=<hostname> is added unless given
+    #[arg(long = "tag", value_name = "KEY=VALUE", value_parser = parse_tag)]
+    pub tags: Vec<(String, String)>,
+
+    /// Number of samples to collect before each push
+    #[arg(long, value_name = "N", default_value_t = 1, value_parser = clap::value_parser!(u64).range(1..))]
+    pub batch: u64,
+
+    /// Maximum number of lines kept while an endpoint is down; the oldest are dropped first
+    #[arg(long, value_name = "LINES", default_value_t = 100_000)]
+    pub buffer: usize,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Target {
+    Http {
+        host: String,
+        port: u16,
+        path: String,
+    },
+    Udp(String),
+    Tcp(String),
+}
+
+impl fmt::Display for Target {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Target::Http { host, port, path } => write!(f, "http://{}:{}{}", host, port, path),
+            Target::Udp(addr) => write!(f, "udp://{}", addr),
+            Target::Tcp(addr) => write!(f, "tcp://{}", addr),
+        }
+    }
+}
+
+#[derive(Debug, Args)]
+pub struct ServeArgs {
+    /// Address for the Prometheus /metrics endpoint, e.g. :9101 or 127.0.0.1:9101
+    #[arg(long, value_name = "ADDR", value_parser = parse_listen)]
+    pub prometheus: SocketAddr,
+
+    /// Number of processes to expose, busiest first
+    #[arg(long, value_name = "N", default_value_t = 0)]
+    pub top: usize,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum ColorMode {
+    Auto,
+    Always,
+    Never,
+}
+
+impl ColorMode {
+    pub fn enabled(self) -> bool {
+        match self {
+            ColorMode::Auto => {
+                std::io::stdout().is_terminal()
+                    && std::env::var_os("NO_COLOR").is_none_or(|v| v.is_empty())
+            }
+            ColorMode::Always => true,
+            ColorMode::Never => false,
+        }
+    }
+}
+
+fn parse_interval(value: &str) -> Result<Duration, String> {
+    let ms: u64 = value
+        .parse()
+        .map_err(|_| format!("`{}` is not a number of milliseconds", value))?;
+    if ms < 100 {
+        return Err("refresh intervals must be at least 100 ms".to_string());
+    }
+    Ok(Duration::from_millis(ms))
+}
+
+fn parse_listen(value: &str) -> Result<SocketAddr, String> {
+    let addr = match value.strip_prefix(':') {
+        Some(port) => format!("0.0.0.0:{}", port),
+        None => value.to_string(),
+    };
+    addr.parse().map_err(|_| {
+        format!(
+            "`{}` is not a listen address like :9101 or 127.0.0.1:9101",
+            value
+        )
+    })
+}
+
+fn parse_host_port(value: &str, default_port: u16) -> Result<(String, u16), String> {
+    let (host, port) = match value.rsplit_once(':') {
+        Some((host, port)) if !port.contains(']') => (
+            host,
+            port.parse()
+                .map_err(|_| format!("`{}` is not a valid port", port))?,
+        ),
+        _ => (value, default_port),
+    };
+    if host.is_empty() {
+        return Err(format!("`{}` has no host", value));
+    }
+    Ok((host.to_string(), port))
+}
+
+fn parse_influx(value: &str) -> Result<Target, String> {
+    if let Some(rest) = value.strip_prefix("http://") {
+        let (authority, path) = match rest.find('/') {
+            Some(i) => rest.split_at(i),
+            None => (rest, "/write"),
+        };
+        let (host, port) = parse_host_port(authority, 8086)?;
+        Ok(Target::Http {
+            host,
+            port,
+            path: path.to_string(),
+        })
+    } else if let Some(rest) = value.strip_prefix("udp://") {
+        let (host, port) = parse_host_port(rest, 8089)?;
+        Ok(Target::Udp(format!("{}:{}", host, port)))
+    } else {
+        Err(format!(
+            "`{}` must start with http:// or udp:// (https is not supported)",
+            value
+        ))
+    }
+}
+
+fn parse_graphite(value: &str) -> Result<Target, String> {
+    let (host, port) = parse_host_port(value.strip_prefix("tcp://").unwrap_or(value), 2003)?;
+    Ok(Target::Tcp(format!("{}:{}", host, port)))
+}
+
+fn parse_tag(value: &str) -> Result<(String, String), String> {
+    match value.split_once('=') {
+        Some((key, value)) if !key.is_empty() && !value.is_empty() => {
+            Ok((key.to_string(), value.to_string()))
+        }
+        _ => Err(format!("`{}` is not a KEY=VALUE tag", value)),
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Overrides {
+    interval: Option<Duration>,
+    units: Option<RateUnit>,
+}
+
+impl Overrides {
+    pub fn apply(&self, mut config: Config) -> Config {
+        if let Some(interval) = self.interval {
+            config.refresh.interval_ms = Some(Millis(interval));
+        }
+        if let Some(units) = self.units {
+            config.units.rate = units;
+        }
+        config
+    }
+}
+
+impl Cli {
+    pub fn overrides(&self) -> Overrides {
+        Overrides {
+            interval: self.interval,
+            units: self.units,
+        }
+    }
+}
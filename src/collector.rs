@@ -0,0 +1,196 @@
+use crate::{
+    config::RefreshConfig,
+    cpu::{self, CoreSample},
+    disk::{self, DiskSample},
+    diskstats::{self, DiskIoSample, DiskIoTracker},
+    host::{self, HostInfo},
+    memory::{self, MemorySample},
+    network::{self, Counters, InterfaceSample},
+    planner::{self, RefreshPlanner, Source},
+    process::{self, IoRates, ProcessInfo},
+    sensors::{SensorSample, SensorSource},
+};
+use std::{
+    collections::HashSet,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+use sysinfo::{Disks, Networks, Pid, ProcessRefreshKind, ProcessesToUpdate, System, Users};
+use tokio::{
+    sync::watch,
+    time::{self, MissedTickBehavior},
+};
+
+#[derive(Clone, Debug, Default)]
+pub struct Overhead {
+    pub cpu: f32,
+    pub memory: u64,
+    pub sample_time: Duration,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct Snapshot {
+    pub host: HostInfo,
+    pub cpu_usage: f32,
+    pub cores: Vec<CoreSample>,
+    pub memory: MemorySample,
+    pub interfaces: Vec<InterfaceSample>,
+    pub disks: Vec<DiskSample>,
+    pub disk_io: Vec<DiskIoSample>,
+    pub sensors: Vec<SensorSample>,
+    pub processes: Vec<ProcessInfo>,
+    pub overhead: Overhead,
+}
+
+pub struct Collector {
+    sys: System,
+    procs: System,
+    own: System,
+    own_pid: Option<Pid>,
+    networks: Networks,
+    disks: Disks,
+    disk_io: DiskIoTracker,
+    sensors: SensorSource,
+    users: Users,
+    planner: RefreshPlanner,
+    net_counters: Counters,
+    io_rates: IoRates,
+    last: Snapshot,
+}
+
+impl Collector {
+    pub fn new(refresh: &RefreshConfig) -> Self {
+        Self {
+            sys: System::new(),
+            procs: System::new(),
+            own: System::new(),
+            own_pid: sysinfo::get_current_pid().ok(),
+            networks: Networks::new_with_refreshed_list(),
+            disks: Disks::new(),
+            disk_io: DiskIoTracker::new(diskstats::source_path()),
+            sensors: SensorSource::from_env(),
+            users: Users::new_with_refreshed_list(),
+            planner: RefreshPlanner::new(refresh),
+            net_counters: Counters::new(),
+            io_rates: IoRates::new(),
+            last: Snapshot::default(),
+        }
+    }
+
+    pub fn tick(&self) -> Duration {
+        self.planner.tick()
+    }
+
+    pub fn configure(&mut self, refresh: &RefreshConfig) {
+        self.planner.configure(refresh);
+    }
+
+    pub fn sample_all(&mut self) -> Snapshot {
+        self.planner.reset();
+        self.sample(&Source::ALL.into_iter().collect())
+    }
+
+    pub fn sample(&mut self, visible: &HashSet<Source>) -> Snapshot {
+        let started = Instant::now();
+        let due = self.planner.due(started, visible);
+
+        self.sys.refresh_specifics(planner::system_refresh(&due));
+        if due.contains(&Source::Cpu) {
+            self.last.cpu_usage = self.sys.global_cpu_usage();
+            self.last.cores = cpu::collect(&self.sys);
+        }
+
+        if due.contains(&Source::Memory) {
+            self.last.memory = memory::collect(&self.sys);
+        }
+
+        if due.contains(&Source::Network) {
+            self.networks.refresh(true);
+            self.last.interfaces =
+                network::collect(&self.networks, &mut self.net_counters, Instant::now());
+        }
+
+        if due.contains(&Source::Disks) {
+            self.disks.refresh(true);
+            self.last.disks = disk::collect(&self.disks);
+        }
+
+        if due.contains(&Source::DiskIo) {
+            self.last.disk_io = self.disk_io.sample(Instant::now());
+        }
+
+        if due.contains(&Source::Host) {
+            self.last.host = host::collect();
+        }
+
+        if due.contains(&Source::Sensors) {
+            self.last.sensors = self.sensors.sample();
+        }
+
+        if due.contains(&Source::Processes) {
+            self.procs.refresh_processes_specifics(
+                ProcessesToUpdate::All,
+                true,
+                planner::process_refresh(),
+            );
+            self.last.processes =
+                process::collect(&self.procs, &self.users, &mut self.io_rates, Instant::now());
+        }
+
+        if let Some(pid) = self.own_pid {
+            self.own.refresh_processes_specifics(
+                ProcessesToUpdate::Some(&[pid]),
+                true,
+                ProcessRefreshKind::nothing().with_cpu().with_memory(),
+            );
+            if let Some(own) = self.own.process(pid) {
+                self.last.overhead.cpu = own.cpu_usage();
+                self.last.overhead.memory = own.memory();
+            }
+        }
+        self.last.overhead.sample_time = started.elapsed();
+
+        self.last.clone()
+    }
+}
+
+fn ticker(period: Duration) -> time::Interval {
+    let mut ticker = time::interval(period);
+    ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+    ticker
+}
+
+pub fn spawn(
+    mut refresh: watch::Receiver<RefreshConfig>,
+    mut demand: watch::Receiver<HashSet<Source>>,
+) -> watch::Receiver<Arc<Snapshot>> {
+    let (tx, rx) = watch::channel(Arc::new(Snapshot::default()));
+    tokio::spawn(async move {
+        let mut collector = Collector::new(&refresh.borrow_and_update());
+        let mut ticker = ticker(collector.tick());
+        loop {
+            tokio::select! {
+                _ = ticker.tick() => {}
+                Ok(()) = refresh.changed() => {
+                    collector.configure(&refresh.borrow_and_update());
+                    ticker = self::ticker(collector.tick());
+                    continue;
+                }
+            }
+            let visible = demand.borrow_and_update().clone();
+            let sampled = tokio::task::spawn_blocking(move || {
+                let snapshot = collector.sample(&visible);
+                (collector, snapshot)
+            })
+            .await;
+            let Ok((returned, snapshot)) = sampled else {
+                break;
+            };
+            collector = returned;
+            if tx.send(Arc::new(snapshot)).is_err() {
+                break;
+            }
+        }
+    });
+    rx
+}
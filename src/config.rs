@@ -0,0 +1,514 @@
+use crate::{
+    disk::DiskFilter,
+    format::RateUnit,
+    history::Window,
+    layout::{self, Node, Tab},
+    network::InterfaceFilter,
+    planner::Source,
+};
+use serde::{Deserialize, Deserializer, de};
+use std::{
+    collections::BTreeMap,
+    fmt, fs, io,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+use toml::Spanned;
+use tui::style::Color;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Millis(pub Duration);
+
+impl<'de> Deserialize<'de> for Millis {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let ms = u64::deserialize(deserializer)?;
+        if ms < 100 {
+            return Err(de::Error::custom(
+                "refresh intervals must be at least 100 ms",
+            ));
+        }
+        Ok(Millis(Duration::from_millis(ms)))
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RefreshConfig {
+    pub interval_ms: Option<Millis>,
+    pub cpu_ms: Option<Millis>,
+    pub memory_ms: Option<Millis>,
+    pub network_ms: Option<Millis>,
+    pub disks_ms: Option<Millis>,
+    pub disk_io_ms: Option<Millis>,
+    pub host_ms: Option<Millis>,
+    pub sensors_ms: Option<Millis>,
+    pub processes_ms: Option<Millis>,
+}
+
+impl RefreshConfig {
+    pub fn interval(&self, source: Source) -> Duration {
+        let base = self
+            .interval_ms
+            .map(|m| m.0)
+            .unwrap_or(Duration::from_secs(1));
+        let (explicit, factor) = match source {
+            Source::Cpu => (self.cpu_ms, 1),
+            Source::Memory => (self.memory_ms, 1),
+            Source::Network => (self.network_ms, 1),
+            Source::DiskIo => (self.disk_io_ms, 1),
+            Source::Host => (self.host_ms, 2),
+            Source::Sensors => (self.sensors_ms, 2),
+            Source::Processes => (self.processes_ms, 2),
+            Source::Disks => (self.disks_ms, 5),
+        };
+        explicit.map(|m| m.0).unwrap_or(base * factor)
+    }
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LayoutConfig {
+    pub margin: u16,
+    pub preset: Spanned<String>,
+    pub custom: BTreeMap<String, Node>,
+    pub tabs: Vec<TabConfig>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TabConfig {
+    pub title: String,
+    pub layout: Spanned<String>,
+}
+
+impl LayoutConfig {
+    pub fn names(&self) -> Vec<String> {
+        let mut names: Vec<String> = layout::PRESETS.iter().map(|s| s.to_string()).collect();
+        for name in self.custom.keys() {
+            if !names.contains(name) {
+                names.push(name.clone());
+            }
+        }
+        names
+    }
+
+    pub fn resolve(&self, name: &str) -> Option<Node> {
+        self.custom
+            .get(name)
+            .cloned()
+            .or_else(|| layout::preset(name))
+    }
+
+    pub fn tabs(&self) -> Vec<Tab> {
+        let builtin = [
+            ("Overview", self.preset.get_ref().as_str()),
+            ("Processes", "process"),
+            ("Network", "network"),
+            ("Storage", "storage"),
+            ("Sensors", "sensors"),
+        ];
+        builtin
+            .into_iter()
+            .map(|(title, layout)| Tab {
+                title: title.to_string(),
+                layout: layout.to_string(),
+            })
+            .chain(self.tabs.iter().map(|tab| Tab {
+                title: tab.title.clone(),
+                layout: tab.layout.get_ref().clone(),
+            }))
+            .collect()
+    }
+}
+
+impl Default for LayoutConfig {
+    fn default() -> Self {
+        Self {
+            margin: 2,
+            preset: Spanned::new(0..0, "full".to_string()),
+            custom: BTreeMap::new(),
+            tabs: Vec::new(),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ColorValue(pub Color);
+
+impl<'de> Deserialize<'de> for ColorValue {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let name = String::deserialize(deserializer)?;
+        parse_color(&name)
+            .map(ColorValue)
+            .ok_or_else(|| de::Error::custom(format!("unknown colour `{}`", name)))
+    }
+}
+
+pub fn parse_color(name: &str) -> Option<Color> {
+    if let Some(hex) = name.strip_prefix('#') {
+        if hex.len() != 6 {
+            return None;
+        }
+        let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+        return Some(Color::Rgb(channel(0)?, channel(2)?, channel(4)?));
+    }
+    let color = match name.to_lowercase().replace(['-', '_'], "").as_str() {
+        "black" => Color::Black,
+        "red" => Color::Red,
+        "green" => Color::Green,
+        "yellow" => Color::Yellow,
+        "blue" => Color::Blue,
+        "magenta" => Color::Magenta,
+        "cyan" => Color::Cyan,
+        "gray" | "grey" => Color::Gray,
+        "darkgray" | "darkgrey" => Color::DarkGray,
+        "lightred" => Color::LightRed,
+        "lightgreen" => Color::LightGreen,
+        "lightyellow" => Color::LightYellow,
+        "lightblue" => Color::LightBlue,
+        "lightmagenta" => Color::LightMagenta,
+        "lightcyan" => Color::LightCyan,
+        "white" => Color::White,
+        _ => return None,
+    };
+    Some(color)
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ColorConfig {
+    pub cpu: ColorValue,
+    pub memory: ColorValue,
+    pub swap: ColorValue,
+    pub download: ColorValue,
+    pub upload: ColorValue,
+    pub disk_read: ColorValue,
+    pub disk_write: ColorValue,
+    pub header: ColorValue,
+}
+
+impl Default for ColorConfig {
+    fn default() -> Self {
+        Self {
+            cpu: ColorValue(Color::Yellow),
+            memory: ColorValue(Color::Green),
+            swap: ColorValue(Color::LightRed),
+            download: ColorValue(Color::Cyan),
+            upload: ColorValue(Color::Magenta),
+            disk_read: ColorValue(Color::Blue),
+            disk_write: ColorValue(Color::Red),
+            header: ColorValue(Color::Cyan),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct UnitsConfig {
+    pub rate: RateUnit,
+    pub history_window: Window,
+}
+
+impl Default for UnitsConfig {
+    fn default() -> Self {
+        Self {
+            rate: RateUnit::Bytes,
+            history_window: Window::OneMinute,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Threshold {
+    pub warn: f64,
+    pub crit: f64,
+}
+
+impl Threshold {
+    pub const fn new(warn: f64, crit: f64) -> Self {
+        Self { warn, crit }
+    }
+
+    pub fn color(&self, value: f64) -> Color {
+        if value >= self.crit {
+            Color::Red
+        } else if value >= self.warn {
+            Color::Yellow
+        } else {
+            Color::Green
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct ThresholdConfig {
+    pub cpu: Threshold,
+    pub disk: Threshold,
+    pub disk_io: Threshold,
+    pub temperature: Threshold,
+}
+
+impl Default for ThresholdConfig {
+    fn default() -> Self {
+        Self {
+            cpu: Threshold::new(50.0, 80.0),
+            disk: Threshold::new(75.0, 90.0),
+            disk_io: Threshold::new(60.0, 90.0),
+            temperature: Threshold::new(70.0, 85.0),
+        }
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct ThresholdOverride {
+    warn: Option<Spanned<f64>>,
+    crit: Option<Spanned<f64>>,
+}
+
+impl ThresholdOverride {
+    fn merge(&self, default: Threshold) -> Threshold {
+        Threshold::new(
+            self.warn.as_ref().map_or(default.warn, |v| *v.get_ref()),
+            self.crit.as_ref().map_or(default.crit, |v| *v.get_ref()),
+        )
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct ThresholdOverrides {
+    cpu: ThresholdOverride,
+    disk: ThresholdOverride,
+    disk_io: ThresholdOverride,
+    temperature: ThresholdOverride,
+}
+
+impl ThresholdOverrides {
+    fn entries(&self) -> [(&'static str, &ThresholdOverride, Threshold); 4] {
+        let defaults = ThresholdConfig::default();
+        [
+            ("cpu", &self.cpu, defaults.cpu),
+            ("disk", &self.disk, defaults.disk),
+            ("disk_io", &self.disk_io, defaults.disk_io),
+            ("temperature", &self.temperature, defaults.temperature),
+        ]
+    }
+}
+
+impl<'de> Deserialize<'de> for ThresholdConfig {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let overrides = ThresholdOverrides::deserialize(deserializer)?;
+        let [cpu, disk, disk_io, temperature] = overrides
+            .entries()
+            .map(|(_, over, default)| over.merge(default));
+        Ok(ThresholdConfig {
+            cpu,
+            disk,
+            disk_io,
+            temperature,
+        })
+    }
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    pub refresh: RefreshConfig,
+    pub layout: LayoutConfig,
+    pub colors: ColorConfig,
+    pub units: UnitsConfig,
+    pub thresholds: ThresholdConfig,
+    pub network: InterfaceFilter,
+    pub disks: DiskFilter,
+}
+
+#[derive(Debug)]
+pub enum ConfigError {
+    Io(PathBuf, io::Error),
+    Parse {
+        path: PathBuf,
+        line: usize,
+        column: usize,
+        message: String,
+    },
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::Io(path, err) => write!(f, "{}: {}", path.display(), err),
+            ConfigError::Parse {
+                path,
+                line,
+                column,
+                message,
+            } => write!(f, "{}:{}:{}: {}", path.display(), line, column, message),
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+fn position(text: &str, offset: usize) -> (usize, usize) {
+    let before = &text[..offset.min(text.len())];
+    let line = before.matches('\n').count() + 1;
+    let column = before.rsplit('\n').next().unwrap_or("").chars().count() + 1;
+    (line, column)
+}
+
+pub fn parse(path: &Path, text: &str) -> Result<Config, ConfigError> {
+    let error = |offset: Option<usize>, message: String| {
+        let (line, column) = offset
+            .map(|offset| position(text, offset))
+            .unwrap_or((1, 1));
+        ConfigError::Parse {
+            path: path.to_path_buf(),
+            line,
+            column,
+            message,
+        }
+    };
+    let config: Config = toml::from_str(text).map_err(|err| {
+        error(
+            err.span().map(|span| span.start),
+            err.message().trim().to_string(),
+        )
+    })?;
+    #[derive(Deserialize)]
+    struct Document {
+        #[serde(default)]
+        thresholds: ThresholdOverrides,
+    }
+    let document: Document = toml::from_str(text).map_err(|err| {
+        error(
+            err.span().map(|span| span.start),
+            err.message().trim().to_string(),
+        )
+    })?;
+    for (name, over, default) in document.thresholds.entries() {
+        let threshold = over.merge(default);
+        if threshold.warn > threshold.crit {
+            let key = over.warn.as_ref().or(over.crit.as_ref());
+            return Err(error(
+                key.map(|key| key.span().start),
+                format!(
+                    "thresholds.{}: warn ({}) must not be above crit ({})",
+                    name, threshold.warn, threshold.crit
+                ),
+            ));
+        }
+    }
+
+    let layouts = std::iter::once(&config.layout.preset)
+        .chain(config.layout.tabs.iter().map(|tab| &tab.layout));
+    for name in layouts {
+        if config.layout.resolve(name.get_ref()).is_none() {
+            return Err(error(
+                Some(name.span().start),
+                format!(
+                    "unknown layout `{}`, expected one of {}",
+                    name.get_ref(),
+                    config.layout.names().join(", ")
+                ),
+            ));
+        }
+    }
+    Ok(config)
+}
+
+pub fn default_path() -> Option<PathBuf> {
+    let base = std::env::var_os("XDG_CONFIG_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+    Some(base.join("systemcli").join("config.toml"))
+}
+
+pub fn resolve(explicit: Option<&Path>) -> Option<PathBuf> {
+    explicit.map(Path::to_path_buf).or_else(default_path)
+}
+
+pub fn read(path: &Path) -> Result<Config, ConfigError> {
+    let text = fs::read_to_string(path).map_err(|err| ConfigError::Io(path.to_path_buf(), err))?;
+    parse(path, &text)
+}
+
+pub fn load(explicit: Option<&Path>) -> Result<Config, ConfigError> {
+    match explicit {
+        Some(path) => read(path),
+        None => match default_path() {
+            Some(path) if path.exists() => read(&path),
+            _ => Ok(Config::default()),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_str(text: &str) -> Result<Config, ConfigError> {
+        parse(Path::new("config.toml"), text)
+    }
+
+    fn error_at(text: &str) -> (usize, usize, String) {
+        match parse_str(text) {
+            Err(ConfigError::Parse {
+                line,
+                column,
+                message,
+                ..
+            }) => (line, column, message),
+            other => panic!("expected a parse error, got {:?}", other.map(|_| ())),
+        }
+    }
+
+    #[test]
+    fn partial_threshold_falls_back_to_defaults() {
+        let config =
+            parse_str("[thresholds.cpu]\nwarn = 60\n\n[thresholds.temperature]\ncrit = 95\n")
+                .unwrap();
+        let defaults = ThresholdConfig::default();
+        assert_eq!(
+            config.thresholds.cpu,
+            Threshold::new(60.0, defaults.cpu.crit)
+        );
+        assert_eq!(
+            config.thresholds.temperature,
+            Threshold::new(defaults.temperature.warn, 95.0)
+        );
+        assert_eq!(config.thresholds.disk, defaults.disk);
+        assert_eq!(config.thresholds.disk_io, defaults.disk_io);
+    }
+
+    #[test]
+    fn full_threshold_override() {
+        let config = parse_str("[thresholds]\ndisk = { warn = 10, crit = 20 }\n").unwrap();
+        assert_eq!(config.thresholds.disk, Threshold::new(10.0, 20.0));
+    }
+
+    #[test]
+    fn warn_above_merged_crit_is_reported_at_the_key() {
+        let (line, column, message) = error_at("[thresholds.cpu]\nwarn = 90\n");
+        assert_eq!((line, column), (2, 8));
+        assert_eq!(
+            message,
+            "thresholds.cpu: warn (90) must not be above crit (80)"
+        );
+
+        let (line, column, message) = error_at("[thresholds.disk]\n\ncrit = 50\n");
+        assert_eq!((line, column), (3, 8));
+        assert_eq!(
+            message,
+            "thresholds.disk: warn (75) must not be above crit (50)"
+        );
+    }
+
+    #[test]
+    fn unknown_threshold_key_is_rejected() {
+        let (line, _, message) = error_at("[thresholds.cpu]\nwarm = 90\n");
+        assert_eq!(line, 2);
+        assert!(message.contains("unknown field `warm`"), "{}", message);
+    }
+}
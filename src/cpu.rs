@@ -0,0 +1,121 @@
+use crate::config::Threshold;
+use sysinfo::System;
+use tui::{
+    Frame,
+    backend::Backend,
+    layout::Rect,
+    style::Style,
+    text::{Span, Spans},
+    widgets::{Block, Borders, Paragraph},
+};
+
+const DETAILED_WIDTH: u16 = 32;
+const COMPACT_WIDTH: u16 = 14;
+
+#[derive(Clone, Debug)]
+pub struct CoreSample {
+    pub usage: f32,
+    pub frequency: u64,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum CoreLayout {
+    Detailed,
+    Compact,
+}
+
+impl CoreLayout {
+    fn cell_width(self) -> u16 {
+        match self {
+            CoreLayout::Detailed => DETAILED_WIDTH,
+            CoreLayout::Compact => COMPACT_WIDTH,
+        }
+    }
+}
+
+pub fn collect(sys: &System) -> Vec<CoreSample> {
+    sys.cpus()
+        .iter()
+        .map(|cpu| CoreSample {
+            usage: cpu.cpu_usage(),
+            frequency: cpu.frequency(),
+        })
+        .collect()
+}
+
+fn grid(count: usize, area: Rect) -> (CoreLayout, usize) {
+    for layout in [CoreLayout::Detailed, CoreLayout::Compact] {
+        let cols = (area.width / layout.cell_width()).max(1) as usize;
+        if count.div_ceil(cols) <= area.height as usize {
+            return (layout, cols);
+        }
+    }
+    let cols = (area.width / COMPACT_WIDTH).max(1) as usize;
+    (CoreLayout::Compact, cols)
+}
+
+fn bar(usage: f32, width: usize) -> String {
+    let filled = ((usage.clamp(0.0, 100.0) / 100.0) * width as f32).round() as usize;
+    let mut bar = "█".repeat(filled);
+    bar.push_str(&"·".repeat(width - filled));
+    bar
+}
+
+fn cell<'a>(
+    index: usize,
+    core: &CoreSample,
+    layout: CoreLayout,
+    digits: usize,
+    threshold: &Threshold,
+) -> Vec<Span<'a>> {
+    let style = Style::default().fg(threshold.color(core.usage as f64));
+    match layout {
+        CoreLayout::Detailed => {
+            let label = format!("cpu{:<digits$} ", index);
+            let bar_width = (DETAILED_WIDTH as usize).saturating_sub(label.len() + 17);
+            vec![
+                Span::raw(label),
+                Span::styled(bar(core.usage, bar_width), style),
+                Span::styled(format!(" {:>5.1}%", core.usage), style),
+                Span::raw(format!(" {:>5}MHz ", core.frequency)),
+            ]
+        }
+        CoreLayout::Compact => {
+            let label = format!("{:>digits$} ", index);
+            let bar_width = (COMPACT_WIDTH as usize).saturating_sub(label.len() + 6);
+            vec![
+                Span::raw(label),
+                Span::styled(bar(core.usage, bar_width), style),
+                Span::styled(format!("{:>4.0}% ", core.usage), style),
+            ]
+        }
+    }
+}
+
+pub fn draw<B: Backend>(f: &mut Frame<B>, area: Rect, cores: &[CoreSample], threshold: &Threshold) {
+    let block = Block::default().borders(Borders::ALL);
+    let inner = block.inner(area);
+    let (layout, cols) = grid(cores.len(), inner);
+    let visible = (cols * inner.height as usize).min(cores.len());
+
+    let mut title = format!("Cores ({})", cores.len());
+    if visible < cores.len() {
+        title.push_str(&format!(" - {} hidden", cores.len() - visible));
+    }
+
+    let digits = cores.len().saturating_sub(1).to_string().len();
+    let lines: Vec<Spans> = cores[..visible]
+        .chunks(cols)
+        .enumerate()
+        .map(|(row, chunk)| {
+            let spans = chunk
+                .iter()
+                .enumerate()
+                .flat_map(|(col, core)| cell(row * cols + col, core, layout, digits, threshold))
+                .collect::<Vec<_>>();
+            Spans::from(spans)
+        })
+        .collect();
+
+    f.render_widget(Paragraph::new(lines).block(block.title(title)), area);
+}
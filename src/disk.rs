@@ -0,0 +1,170 @@
+use crate::{config::Threshold, format};
+use serde::Deserialize;
+use std::{ffi::CString, os::unix::ffi::OsStrExt, path::Path};
+use sysinfo::Disks;
+use tui::{
+    Frame,
+    backend::Backend,
+    layout::{Constraint, Rect},
+    style::{Color, Modifier, Style},
+    widgets::{Block, Borders, Cell, Row, Table},
+};
+
+const PSEUDO_FILESYSTEMS: [&str; 8] = [
+    "tmpfs",
+    "devtmpfs",
+    "overlay",
+    "squashfs",
+    "ramfs",
+    "efivarfs",
+    "fuse.lxcfs",
+    "nsfs",
+];
+
+#[derive(Clone, Debug)]
+pub struct DiskSample {
+    pub device: String,
+    pub mount_point: String,
+    pub fs_type: String,
+    pub total: u64,
+    pub used: u64,
+    pub available: u64,
+    pub inodes_total: u64,
+    pub inodes_used: u64,
+}
+
+impl DiskSample {
+    pub fn used_percent(&self) -> f64 {
+        percent(self.used, self.total)
+    }
+
+    pub fn inodes_percent(&self) -> f64 {
+        percent(self.inodes_used, self.inodes_total)
+    }
+}
+
+fn percent(used: u64, total: u64) -> f64 {
+    if total == 0 {
+        0.0
+    } else {
+        used as f64 / total as f64 * 100.0
+    }
+}
+
+pub fn inodes(path: &Path) -> Option<(u64, u64)> {
+    let path = CString::new(path.as_os_str().as_bytes()).ok()?;
+    let mut stat: libc::statvfs = unsafe { std::mem::zeroed() };
+    if unsafe { libc::statvfs(path.as_ptr(), &mut stat) } != 0 {
+        return None;
+    }
+    let total = stat.f_files as u64;
+    Some((total, total.saturating_sub(stat.f_ffree as u64)))
+}
+
+pub fn collect(disks: &Disks) -> Vec<DiskSample> {
+    let mut samples: Vec<DiskSample> = disks
+        .iter()
+        .map(|disk| {
+            let (inodes_total, inodes_used) = inodes(disk.mount_point()).unwrap_or((0, 0));
+            DiskSample {
+                device: disk.name().to_string_lossy().into_owned(),
+                mount_point: disk.mount_point().to_string_lossy().into_owned(),
+                fs_type: disk.file_system().to_string_lossy().into_owned(),
+                total: disk.total_space(),
+                used: disk.total_space().saturating_sub(disk.available_space()),
+                available: disk.available_space(),
+                inodes_total,
+                inodes_used,
+            }
+        })
+        .collect();
+    samples.sort_by(|a, b| a.mount_point.cmp(&b.mount_point));
+    samples
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DiskFilter {
+    pub hidden_fs: Vec<String>,
+    pub show_pseudo: bool,
+}
+
+impl Default for DiskFilter {
+    fn default() -> Self {
+        Self {
+            hidden_fs: PSEUDO_FILESYSTEMS.iter().map(|fs| fs.to_string()).collect(),
+            show_pseudo: false,
+        }
+    }
+}
+
+impl DiskFilter {
+    pub fn matches(&self, disk: &DiskSample) -> bool {
+        self.show_pseudo || !self.hidden_fs.contains(&disk.fs_type)
+    }
+}
+
+pub fn draw<B: Backend>(
+    f: &mut Frame<B>,
+    area: Rect,
+    disks: &[DiskSample],
+    filter: &DiskFilter,
+    threshold: &Threshold,
+) {
+    let header = Row::new(vec![
+        "Mount",
+        "Device",
+        "Type",
+        "Total",
+        "Used",
+        "Available",
+        "Use%",
+        "Inodes%",
+    ])
+    .style(
+        Style::default()
+            .fg(Color::Yellow)
+            .add_modifier(Modifier::BOLD),
+    );
+    let visible: Vec<&DiskSample> = disks.iter().filter(|d| filter.matches(d)).collect();
+    let rows = visible.iter().map(|d| {
+        let used = d.used_percent();
+        let inodes = d.inodes_percent();
+        Row::new(vec![
+            Cell::from(d.mount_point.clone()),
+            Cell::from(d.device.clone()),
+            Cell::from(d.fs_type.clone()),
+            Cell::from(format::bytes(d.total)),
+            Cell::from(format::bytes(d.used)),
+            Cell::from(format::bytes(d.available)),
+            Cell::from(format!("{:.1}%", used)).style(Style::default().fg(threshold.color(used))),
+            Cell::from(if d.inodes_total == 0 {
+                "-".to_string()
+            } else {
+                format!("{:.1}%", inodes)
+            })
+            .style(Style::default().fg(threshold.color(inodes))),
+        ])
+    });
+    let hidden = disks.len() - visible.len();
+    let title = if filter.show_pseudo {
+        "Filesystems - all (f hides pseudo)".to_string()
+    } else {
+        format!("Filesystems - {} pseudo hidden (f shows)", hidden)
+    };
+    let widths = [
+        Constraint::Min(16),
+        Constraint::Length(16),
+        Constraint::Length(8),
+        Constraint::Length(10),
+        Constraint::Length(10),
+        Constraint::Length(10),
+        Constraint::Length(7),
+        Constraint::Length(8),
+    ];
+    let table = Table::new(rows)
+        .header(header)
+        .block(Block::default().title(title).borders(Borders::ALL))
+        .widths(&widths);
+    f.render_widget(table, area);
+}
@@ -0,0 +1,404 @@
+use crate::{
+    config::Threshold,
+    format::{self, RateUnit},
+};
+use std::{
+    collections::HashMap,
+    fs, io,
+    path::{Path, PathBuf},
+    time::Instant,
+};
+use tui::{
+    Frame,
+    backend::Backend,
+    layout::{Constraint, Rect},
+    style::{Color, Modifier, Style},
+    widgets::{Block, Borders, Cell, Row, Table},
+};
+
+const SECTOR_SIZE: u64 = 512;
+const DEFAULT_PATH: &str = "/proc/diskstats";
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct DiskStat {
+    pub major: u32,
+    pub minor: u32,
+    pub name: String,
+    pub reads: u64,
+    pub sectors_read: u64,
+    pub read_ms: u64,
+    pub writes: u64,
+    pub sectors_written: u64,
+    pub write_ms: u64,
+    pub in_flight: u64,
+    pub io_ms: u64,
+    pub weighted_io_ms: u64,
+}
+
+pub fn parse(text: &str) -> Vec<DiskStat> {
+    text.lines().filter_map(parse_line).collect()
+}
+
+fn parse_line(line: &str) -> Option<DiskStat> {
+    let mut fields = line.split_whitespace();
+    let major = fields.next()?.parse().ok()?;
+    let minor = fields.next()?.parse().ok()?;
+    let name = fields.next()?.to_string();
+    let values: Vec<u64> = fields.map(|v| v.parse()).collect::<Result<_, _>>().ok()?;
+    if values.len() < 11 {
+        return None;
+    }
+    Some(DiskStat {
+        major,
+        minor,
+        name,
+        reads: values[0],
+        sectors_read: values[2],
+        read_ms: values[3],
+        writes: values[4],
+        sectors_written: values[6],
+        write_ms: values[7],
+        in_flight: values[8],
+        io_ms: values[9],
+        weighted_io_ms: values[10],
+    })
+}
+
+pub fn source_path() -> PathBuf {
+    std::env::var_os("SYSTEMCLI_DISKSTATS")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from(DEFAULT_PATH))
+}
+
+pub fn read(path: &Path) -> io::Result<Vec<DiskStat>> {
+    fs::read_to_string(path).map(|text| parse(&text))
+}
+
+fn is_partition(stat: &DiskStat, stats: &[DiskStat]) -> bool {
+    stats.iter().any(|disk| {
+        disk.major == stat.major
+            && disk.minor < stat.minor
+            && stat.name.strip_prefix(&disk.name).is_some_and(|rest| {
+                let number = if disk.name.ends_with(|c: char| c.is_ascii_digit()) {
+                    rest.strip_prefix('p')
+                } else {
+                    Some(rest)
+                };
+                number.is_some_and(|n| !n.is_empty() && n.bytes().all(|b| b.is_ascii_digit()))
+            })
+    })
+}
+
+fn whole_disks(stats: Vec<DiskStat>, sysfs: Option<&Path>) -> Vec<DiskStat> {
+    let partitions: Vec<bool> = stats.iter().map(|s| is_partition(s, &stats)).collect();
+    stats
+        .into_iter()
+        .zip(partitions)
+        .filter(|(stat, partition)| {
+            if stat.name.starts_with("loop") || stat.name.starts_with("ram") {
+                return false;
+            }
+            match sysfs.filter(|block| block.exists()) {
+                Some(block) => block.join(&stat.name).exists(),
+                None => !partition,
+            }
+        })
+        .map(|(stat, _)| stat)
+        .collect()
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct DiskIoSample {
+    pub name: String,
+    pub read_rate: f64,
+    pub write_rate: f64,
+    pub read_iops: f64,
+    pub write_iops: f64,
+    pub await_ms: f64,
+    pub utilization: f64,
+    pub queue_depth: f64,
+    pub in_flight: u64,
+    pub read_bytes: u64,
+    pub write_bytes: u64,
+    pub reads: u64,
+    pub writes: u64,
+    pub io_ms: u64,
+}
+
+pub fn delta(prev: &DiskStat, cur: &DiskStat, elapsed_secs: f64) -> DiskIoSample {
+    let mut sample = DiskIoSample {
+        name: cur.name.clone(),
+        in_flight: cur.in_flight,
+        read_bytes: cur.sectors_read * SECTOR_SIZE,
+        write_bytes: cur.sectors_written * SECTOR_SIZE,
+        reads: cur.reads,
+        writes: cur.writes,
+        io_ms: cur.io_ms,
+        ..DiskIoSample::default()
+    };
+    if elapsed_secs <= 0.0 {
+        return sample;
+    }
+    let reads = cur.reads.saturating_sub(prev.reads);
+    let writes = cur.writes.saturating_sub(prev.writes);
+    let io_time =
+        cur.read_ms.saturating_sub(prev.read_ms) + cur.write_ms.saturating_sub(prev.write_ms);
+    let elapsed_ms = elapsed_secs * 1000.0;
+
+    sample.read_rate =
+        (cur.sectors_read.saturating_sub(prev.sectors_read) * SECTOR_SIZE) as f64 / elapsed_secs;
+    sample.write_rate = (cur.sectors_written.saturating_sub(prev.sectors_written) * SECTOR_SIZE)
+        as f64
+        / elapsed_secs;
+    sample.read_iops = reads as f64 / elapsed_secs;
+    sample.write_iops = writes as f64 / elapsed_secs;
+    if reads + writes > 0 {
+        sample.await_ms = io_time as f64 / (reads + writes) as f64;
+    }
+    sample.utilization =
+        (cur.io_ms.saturating_sub(prev.io_ms) as f64 / elapsed_ms * 100.0).min(100.0);
+    sample.queue_depth = cur.weighted_io_ms.saturating_sub(prev.weighted_io_ms) as f64 / elapsed_ms;
+    sample
+}
+
+pub struct DiskIoTracker {
+    path: PathBuf,
+    prev: HashMap<String, (Instant, DiskStat)>,
+}
+
+impl DiskIoTracker {
+    pub fn new(path: PathBuf) -> Self {
+        Self {
+            path,
+            prev: HashMap::new(),
+        }
+    }
+
+    pub fn sample(&mut self, now: Instant) -> Vec<DiskIoSample> {
+        let Ok(stats) = read(&self.path) else {
+            return Vec::new();
+        };
+        let sysfs = (self.path == Path::new(DEFAULT_PATH)).then_some(Path::new("/sys/block"));
+        let mut prev = HashMap::with_capacity(stats.len());
+        let samples = whole_disks(stats, sysfs)
+            .into_iter()
+            .map(|stat| {
+                let sample = match self.prev.get(&stat.name) {
+                    Some((then, old)) => delta(
+                        old,
+                        &stat,
+                        now.saturating_duration_since(*then).as_secs_f64(),
+                    ),
+                    None => delta(&stat, &stat, 0.0),
+                };
+                prev.insert(stat.name.clone(), (now, stat));
+                sample
+            })
+            .collect();
+        self.prev = prev;
+        samples
+    }
+}
+
+pub fn draw<B: Backend>(
+    f: &mut Frame<B>,
+    area: Rect,
+    devices: &[DiskIoSample],
+    unit: RateUnit,
+    threshold: &Threshold,
+) {
+    let header = Row::new(vec![
+        "Device", "Read", "Write", "R IOPS", "W IOPS", "Await", "Util%", "Queue",
+    ])
+    .style(
+        Style::default()
+            .fg(Color::Yellow)
+            .add_modifier(Modifier::BOLD),
+    );
+    let rows = devices.iter().map(|d| {
+        Row::new(vec![
+            Cell::from(d.name.clone()),
+            Cell::from(format::rate(d.read_rate, unit)),
+            Cell::from(format::rate(d.write_rate, unit)),
+            Cell::from(format!("{:.0}", d.read_iops)),
+            Cell::from(format!("{:.0}", d.write_iops)),
+            Cell::from(format!("{:.1}ms", d.await_ms)),
+            Cell::from(format!("{:.0}%", d.utilization))
+                .style(Style::default().fg(threshold.color(d.utilization))),
+            Cell::from(format!("{:.1}/{}", d.queue_depth, d.in_flight)),
+        ])
+    });
+    let widths = [
+        Constraint::Min(8),
+        Constraint::Length(10),
+        Constraint::Length(10),
+        Constraint::Length(7),
+        Constraint::Length(7),
+        Constraint::Length(8),
+        Constraint::Length(6),
+        Constraint::Length(8),
+    ];
+    let table = Table::new(rows)
+        .header(header)
+        .block(Block::default().title("Block I/O").borders(Borders::ALL))
+        .widths(&widths);
+    f.render_widget(table, area);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixture(name: &str) -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("fixtures/diskstats")
+            .join(name)
+    }
+
+    fn names(stats: &[DiskStat]) -> Vec<&str> {
+        stats.iter().map(|s| s.name.as_str()).collect()
+    }
+
+    fn stat(reads: u64, read_ms: u64, writes: u64, write_ms: u64, io_ms: u64) -> DiskStat {
+        DiskStat {
+            name: "sda".to_string(),
+            reads,
+            sectors_read: reads * 8,
+            read_ms,
+            writes,
+            sectors_written: writes * 16,
+            write_ms,
+            io_ms,
+            weighted_io_ms: read_ms + write_ms,
+            ..DiskStat::default()
+        }
+    }
+
+    #[test]
+    fn skips_short_and_malformed_lines() {
+        let stats = read(&fixture("short.txt")).unwrap();
+        assert_eq!(names(&stats), ["sdb"]);
+        assert_eq!(stats[0].major, 8);
+        assert_eq!(stats[0].minor, 16);
+        assert_eq!(stats[0].sectors_read, 400);
+    }
+
+    #[test]
+    fn parses_14_field_format() {
+        let stats = read(&fixture("fields-14.txt")).unwrap();
+        assert_eq!(names(&stats), ["loop0", "sda", "sda1", "sdb"]);
+        let sda = &stats[1];
+        assert_eq!(
+            (sda.reads, sda.sectors_read, sda.read_ms),
+            (1000, 20000, 500)
+        );
+        assert_eq!(
+            (sda.writes, sda.sectors_written, sda.write_ms),
+            (2000, 40000, 1500)
+        );
+        assert_eq!(
+            (sda.in_flight, sda.io_ms, sda.weighted_io_ms),
+            (0, 1800, 2000)
+        );
+    }
+
+    #[test]
+    fn parses_18_field_format() {
+        let stats = read(&fixture("fields-18.txt")).unwrap();
+        assert_eq!(
+            names(&stats),
+            ["nvme0n1", "nvme0n1p1", "dm-0", "nvme0n10", "nvme0n10p1"]
+        );
+        let nvme = &stats[0];
+        assert_eq!((nvme.reads, nvme.writes, nvme.in_flight), (5000, 8000, 2));
+        assert_eq!((nvme.io_ms, nvme.weighted_io_ms), (9000, 14500));
+    }
+
+    #[test]
+    fn parses_20_field_format() {
+        let stats = read(&fixture("fields-20.txt")).unwrap();
+        assert_eq!(names(&stats), ["mmcblk0", "mmcblk0p1", "ram0"]);
+        let mmc = &stats[0];
+        assert_eq!((mmc.sectors_read, mmc.sectors_written), (6000, 8000));
+        assert_eq!((mmc.io_ms, mmc.weighted_io_ms), (700, 1020));
+    }
+
+    #[test]
+    fn drops_partitions_and_virtual_devices_without_sysfs() {
+        for (file, expected) in [
+            ("fields-14.txt", vec!["sda", "sdb"]),
+            ("fields-18.txt", vec!["nvme0n1", "dm-0", "nvme0n10"]),
+            ("fields-20.txt", vec!["mmcblk0"]),
+        ] {
+            let stats = whole_disks(read(&fixture(file)).unwrap(), None);
+            assert_eq!(names(&stats), expected, "{}", file);
+        }
+    }
+
+    #[test]
+    fn tracker_reads_fixture_without_real_hardware() {
+        let mut tracker = DiskIoTracker::new(fixture("fields-18.txt"));
+        let samples = tracker.sample(Instant::now());
+        let names: Vec<&str> = samples.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, ["nvme0n1", "dm-0", "nvme0n10"]);
+        assert_eq!(samples[0].read_bytes, 400000 * SECTOR_SIZE);
+        assert_eq!(samples[0].read_rate, 0.0);
+    }
+
+    #[test]
+    fn computes_rates_await_util_and_queue() {
+        let prev = stat(1000, 2000, 500, 1000, 10000);
+        let cur = stat(1100, 2300, 550, 1150, 10500);
+        let sample = delta(&prev, &cur, 1.0);
+        assert_eq!(sample.read_rate, (100 * 8 * SECTOR_SIZE) as f64);
+        assert_eq!(sample.write_rate, (50 * 16 * SECTOR_SIZE) as f64);
+        assert_eq!(sample.read_iops, 100.0);
+        assert_eq!(sample.write_iops, 50.0);
+        assert_eq!(sample.await_ms, 3.0);
+        assert_eq!(sample.utilization, 50.0);
+        assert_eq!(sample.queue_depth, 0.45);
+
+        let sample = delta(&prev, &cur, 2.0);
+        assert_eq!(sample.read_iops, 50.0);
+        assert_eq!(sample.utilization, 25.0);
+    }
+
+    #[test]
+    fn idle_disk_has_no_await() {
+        let prev = stat(1000, 2000, 500, 1000, 10000);
+        let sample = delta(&prev, &prev, 1.0);
+        assert_eq!(sample.await_ms, 0.0);
+        assert_eq!(sample.utilization, 0.0);
+    }
+
+    #[test]
+    fn clamps_utilization_to_100() {
+        let prev = stat(0, 0, 0, 0, 0);
+        let cur = stat(10, 10, 10, 10, 2500);
+        assert_eq!(delta(&prev, &cur, 1.0).utilization, 100.0);
+    }
+
+    #[test]
+    fn counter_reset_yields_zero_rates() {
+        let prev = stat(1000, 2000, 500, 1000, 10000);
+        let cur = stat(10, 20, 5, 10, 100);
+        let sample = delta(&prev, &cur, 1.0);
+        assert_eq!(sample.read_rate, 0.0);
+        assert_eq!(sample.write_rate, 0.0);
+        assert_eq!(sample.read_iops, 0.0);
+        assert_eq!(sample.write_iops, 0.0);
+        assert_eq!(sample.await_ms, 0.0);
+        assert_eq!(sample.utilization, 0.0);
+        assert_eq!(sample.queue_depth, 0.0);
+        assert_eq!(sample.reads, 10);
+    }
+
+    #[test]
+    fn zero_elapsed_time_keeps_counters_only() {
+        let prev = stat(1000, 2000, 500, 1000, 10000);
+        let cur = stat(1100, 2300, 550, 1150, 10500);
+        let sample = delta(&prev, &cur, 0.0);
+        assert_eq!(sample.read_rate, 0.0);
+        assert_eq!(sample.writes, 550);
+    }
+}
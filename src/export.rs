@@ -0,0 +1,1092 @@
+use crate::{
+    cli::{ExportArgs, Target},
+    collector::{self, Snapshot},
+    config::Config,
+    planner::Source,
+};
+use std::{
+    collections::VecDeque,
+    error::Error,
+    io,
+    os::fd::AsRawFd,
+    pin::Pin,
+    sync::Arc,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+use sysinfo::System;
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::{TcpStream, UdpSocket},
+    sync::watch,
+    task::JoinSet,
+    time::Instant,
+};
+
+const SEND_TIMEOUT: Duration = Duration::from_secs(10);
+const MIN_BACKOFF: Duration = Duration::from_secs(1);
+const MAX_BACKOFF: Duration = Duration::from_secs(60);
+const MAX_LINES_PER_WRITE: usize = 5000;
+const MAX_DATAGRAM: usize = 1400;
+
+struct Point {
+    measurement: &'static str,
+    tags: Vec<(&'static str, String)>,
+    fields: Vec<(&'static str, f64)>,
+}
+
+fn points(snapshot: &Snapshot) -> Vec<Point> {
+    let mut points = vec![Point {
+        measurement: "cpu",
+        tags: vec![("core", "total".to_string())],
+        fields: vec![("usage_percent", snapshot.cpu_usage as f64)],
+    }];
+    for (i, core) in snapshot.cores.iter().enumerate() {
+        points.push(Point {
+            measurement: "cpu",
+            tags: vec![("core", i.to_string())],
+            fields: vec![
+                ("usage_percent", core.usage as f64),
+                ("frequency_mhz", core.frequency as f64),
+            ],
+        });
+    }
+
+    let memory = &snapshot.memory;
+    points.push(Point {
+        measurement: "mem",
+        tags: Vec::new(),
+        fields: vec![
+            ("total", memory.total as f64),
+            ("used", memory.used as f64),
+            ("available", memory.available as f64),
+            ("buffers", memory.buffers as f64),
+            ("cached", memory.cached as f64),
+            ("used_percent", memory.used_percent().unwrap_or(f64::NAN)),
+        ],
+    });
+    points.push(Point {
+        measurement: "swap",
+        tags: Vec::new(),
+        fields: vec![
+            ("total", memory.swap_total as f64),
+            ("used", memory.swap_used as f64),
+            ("used_percent", memory.swap_percent().unwrap_or(f64::NAN)),
+        ],
+    });
+
+    for iface in &snapshot.interfaces {
+        points.push(Point {
+            measurement: "net",
+            tags: vec![("interface", iface.name.clone())],
+            fields: vec![
+                ("rx_bytes_per_sec", iface.rx_rate),
+                ("tx_bytes_per_sec", iface.tx_rate),
+                ("rx_bytes", iface.rx_total as f64),
+                ("tx_bytes", iface.tx_total as f64),
+                ("rx_errors", iface.rx_errors as f64),
+                ("tx_errors", iface.tx_errors as f64),
+                ("rx_dropped", iface.rx_dropped as f64),
+                ("tx_dropped", iface.tx_dropped as f64),
+            ],
+        });
+    }
+
+    for disk in &snapshot.disk_io {
+        points.push(Point {
+            measurement: "diskio",
+            tags: vec![("device", disk.name.clone())],
+            fields: vec![
+                ("read_bytes_per_sec", disk.read_rate),
+                ("write_bytes_per_sec", disk.write_rate),
+                ("read_iops", disk.read_iops),
+                ("write_iops", disk.write_iops),
+                ("await_ms", disk.await_ms),
+                ("util_percent", disk.utilization),
+                ("read_bytes", disk.read_bytes as f64),
+                ("write_bytes", disk.write_bytes as f64),
+            ],
+        });
+    }
+
+    for fs in &snapshot.disks {
+        points.push(Point {
+            measurement: "fs",
+            tags: vec![
+                ("mountpoint", fs.mount_point.clone()),
+                ("device", fs.device.clone()),
+                ("fstype", fs.fs_type.clone()),
+            ],
+            fields: vec![
+                ("total", fs.total as f64),
+                ("used", fs.used as f64),
+                ("available", fs.available as f64),
+                ("used_percent", fs.used_percent()),
+            ],
+        });
+    }
+
+    let host = &snapshot.host;
+    points.push(Point {
+        measurement: "system",
+        tags: Vec::new(),
+        fields: vec![
+            ("load1", host.load[0]),
+            ("load5", host.load[1]),
+            ("load15", host.load[2]),
+            ("uptime", host.uptime as f64),
+            ("processes", host.processes as f64),
+            ("threads", host.threads as f64),
+        ],
+    });
+
+    for sensor in &snapshot.sensors {
+        if let Some(temp) = sensor.temperature {
+            points.push(Point {
+                measurement: "temp",
+                tags: vec![("sensor", sensor.label.clone())],
+                fields: vec![("celsius", temp as f64)],
+            });
+        }
+    }
+    points
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Protocol {
+    Influx,
+    Graphite,
+}
+
+fn influx_escape(value: &str, special: &[char]) -> String {
+    let mut out = String::with_capacity(value.len());
+    for c in value.chars() {
+        if c == '\\' || special.contains(&c) {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+    out
+}
+
+fn graphite_segment(value: &str) -> String {
+    let segment: String = value
+        .chars()
+        .map(|c| match c {
+            'a'..='z' | 'A'..='Z' | '0'..='9' | '-' | '_' => c,
+            _ => '_',
+        })
+        .collect();
+    match segment.trim_matches('_') {
+        "" => "root".to_string(),
+        trimmed => trimmed.to_string(),
+    }
+}
+
+impl Protocol {
+    fn name(self) -> &'static str {
+        match self {
+            Protocol::Influx => "influx",
+            Protocol::Graphite => "graphite",
+        }
+    }
+
+    fn encode(
+        self,
+        points: &[Point],
+        prefix: &str,
+        tags: &[(String, String)],
+        time: Duration,
+    ) -> Vec<String> {
+        let mut lines = Vec::new();
+        for point in points {
+            match self {
+                Protocol::Influx => {
+                    let measurement = match prefix {
+                        "" => point.measurement.to_string(),
+                        _ => format!("{}_{}", prefix, point.measurement),
+                    };
+                    let mut line = influx_escape(&measurement, &[',', ' ']);
+                    let mut all: Vec<(&str, &str)> = tags
+                        .iter()
+                        .map(|(k, v)| (k.as_str(), v.as_str()))
+                        .chain(point.tags.iter().map(|(k, v)| (*k, v.as_str())))
+                        .filter(|(_, v)| !v.is_empty())
+                        .collect();
+                    all.sort_by_key(|(k, _)| *k);
+                    for (key, value) in all {
+                        line.push(',');
+                        line.push_str(&influx_escape(key, &[',', '=', ' ']));
+                        line.push('=');
+                        line.push_str(&influx_escape(value, &[',', '=', ' ']));
+                    }
+                    let fields: Vec<String> = point
+                        .fields
+                        .iter()
+                        .filter(|(_, v)| v.is_finite())
+                        .map(|(k, v)| format!("{}={}", k, v))
+                        .collect();
+                    if fields.is_empty() {
+                        continue;
+                    }
+                    lines.push(format!("{} {} {}", line, fields.join(","), time.as_nanos()));
+                }
+                Protocol::Graphite => {
+                    let mut path: Vec<String> = prefix
+                        .split('.')
+                        .filter(|s| !s.is_empty())
+                        .map(graphite_segment)
+                        .collect();
+                    path.push(point.measurement.to_string());
+                    path.extend(point.tags.iter().map(|(_, v)| graphite_segment(v)));
+                    let suffix: String = tags
+                        .iter()
+                        .map(|(k, v)| format!(";{}={}", graphite_segment(k), graphite_segment(v)))
+                        .collect();
+                    for (field, value) in point.fields.iter().filter(|(_, v)| v.is_finite()) {
+                        lines.push(format!(
+                            "{}.{}{} {} {}",
+                            path.join("."),
+                            field,
+                            suffix,
+                            value,
+                            time.as_secs()
+                        ));
+                    }
+                }
+            }
+        }
+        lines
+    }
+}
+
+enum Transport {
+    Http {
+        host: String,
+        port: u16,
+        path: String,
+        token: Option<String>,
+    },
+    Udp {
+        addr: String,
+        socket: Option<UdpSocket>,
+    },
+    Tcp {
+        addr: String,
+        stream: Option<TcpStream>,
+        unconfirmed: Vec<String>,
+    },
+}
+
+fn peer_closed(stream: &TcpStream) -> bool {
+    let mut byte = 0u8;
+    let n = unsafe {
+        libc::recv(
+            stream.as_raw_fd(),
+            (&mut byte as *mut u8).cast(),
+            1,
+            libc::MSG_PEEK | libc::MSG_DONTWAIT,
+        )
+    };
+    n == 0
+        || (n < 0
+            && !matches!(
+                io::Error::last_os_error().kind(),
+                io::ErrorKind::WouldBlock | io::ErrorKind::Interrupted
+            ))
+}
+
+impl Transport {
+    fn new(target: &Target, token: Option<String>) -> Self {
+        match target.clone() {
+            Target::Http { host, port, path } => Transport::Http {
+                host,
+                port,
+                path,
+                token,
+            },
+            Target::Udp(addr) => Transport::Udp { addr, socket: None },
+            Target::Tcp(addr) => Transport::Tcp {
+                addr,
+                stream: None,
+                unconfirmed: Vec::new(),
+            },
+        }
+    }
+
+    async fn send(&mut self, lines: &[String]) -> io::Result<()> {
+        match self {
+            Transport::Http {
+                host,
+                port,
+                path,
+                token,
+            } => {
+                let body = lines.join("\n") + "\n";
+                let mut request = format!(
+                    "POST {} HTTP/1.1\r\nHost: {}:{}\r\nContent-Type: text/plain; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n",
+                    path,
+                    host,
+                    port,
+                    body.len()
+                );
+                if let Some(token) = token {
+                    request.push_str(&format!("Authorization: Token {}\r\n", token));
+                }
+                request.push_str("\r\n");
+                request.push_str(&body);
+
+                let mut stream = TcpStream::connect((host.as_str(), *port)).await?;
+                stream.write_all(request.as_bytes()).await?;
+                let mut response = Vec::new();
+                (&mut stream)
+                    .take(64 * 1024)
+                    .read_to_end(&mut response)
+                    .await?;
+                let response = String::from_utf8_lossy(&response);
+                let status = response.lines().next().unwrap_or_default();
+                match status.split_whitespace().nth(1) {
+                    Some(code) if code.starts_with('2') => Ok(()),
+                    Some(_) => {
+                        let detail = response
+                            .split_once("\r\n\r\n")
+                            .map(|(_, body)| body.trim())
+                            .unwrap_or_default();
+                        Err(io::Error::other(
+                            format!("{} {}", status, detail).trim().to_string(),
+                        ))
+                    }
+                    None => Err(io::Error::other("malformed HTTP response")),
+                }
+            }
+            Transport::Udp { addr, socket } => {
+                if socket.is_none() {
+                    let target = tokio::net::lookup_host(addr.as_str())
+                        .await?
+                        .next()
+                        .ok_or_else(|| io::Error::other(format!("cannot resolve {}", addr)))?;
+                    let bind = if target.is_ipv4() {
+                        "0.0.0.0:0"
+                    } else {
+                        "[::]:0"
+                    };
+                    let udp = UdpSocket::bind(bind).await?;
+                    udp.connect(target).await?;
+                    *socket = Some(udp);
+                }
+                let Some(udp) = socket else {
+                    return Ok(());
+                };
+                let mut datagram = String::new();
+                for line in lines {
+                    if !datagram.is_empty() && datagram.len() + line.len() + 1 > MAX_DATAGRAM {
+                        udp.send(datagram.as_bytes()).await?;
+                        datagram.clear();
+                    }
+                    datagram.push_str(line);
+                    datagram.push('\n');
+                }
+                if !datagram.is_empty() {
+                    udp.send(datagram.as_bytes()).await?;
+                }
+                Ok(())
+            }
+            Transport::Tcp {
+                addr,
+                stream,
+                unconfirmed,
+            } => {
+                match stream {
+                    Some(tcp) if peer_closed(tcp) => *stream = None,
+                    Some(_) => unconfirmed.clear(),
+                    None => {}
+                }
+                if stream.is_none() {
+                    *stream = Some(TcpStream::connect(addr.as_str()).await?);
+                }
+                let Some(tcp) = stream else {
+                    return Ok(());
+                };
+                let mut body = String::new();
+                for line in unconfirmed.iter().chain(lines) {
+                    body.push_str(line);
+                    body.push('\n');
+                }
+                if let Err(err) = tcp.write_all(body.as_bytes()).await {
+                    *stream = None;
+                    return Err(err);
+                }
+                unconfirmed.extend(lines.iter().cloned());
+                let excess = unconfirmed.len().saturating_sub(MAX_LINES_PER_WRITE);
+                unconfirmed.drain(..excess);
+                Ok(())
+            }
+        }
+    }
+
+    fn reset(&mut self) {
+        match self {
+            Transport::Http { .. } => {}
+            Transport::Udp { socket, .. } => *socket = None,
+            Transport::Tcp { stream, .. } => *stream = None,
+        }
+    }
+}
+
+type Sending = Pin<Box<dyn Future<Output = (Transport, Vec<String>, io::Result<()>)> + Send>>;
+
+struct Exporter {
+    protocol: Protocol,
+    target: Target,
+    transport: Option<Transport>,
+    prefix: String,
+    tags: Vec<(String, String)>,
+    batch: u64,
+    pending: u64,
+    buffer: VecDeque<String>,
+    capacity: usize,
+    dropped: u64,
+    sent: usize,
+    backoff: Duration,
+    retry_at: Option<Instant>,
+}
+
+impl Exporter {
+    fn new(
+        protocol: Protocol,
+        target: &Target,
+        token: Option<String>,
+        args: &ExportArgs,
+        tags: &[(String, String)],
+    ) -> Self {
+        Exporter {
+            protocol,
+            target: target.clone(),
+            transport: Some(Transport::new(target, token)),
+            prefix: args.prefix.clone(),
+            tags: tags.to_vec(),
+            batch: args.batch,
+            pending: 0,
+            buffer: VecDeque::new(),
+            capacity: args.buffer.max(1),
+            dropped: 0,
+            sent: 0,
+            backoff: MIN_BACKOFF,
+            retry_at: None,
+        }
+    }
+
+    fn push(&mut self, points: &[Point], time: Duration) {
+        for line in self.protocol.encode(points, &self.prefix, &self.tags, time) {
+            if self.buffer.len() == self.capacity {
+                self.buffer.pop_front();
+                self.dropped += 1;
+            }
+            self.buffer.push_back(line);
+        }
+        self.pending += 1;
+    }
+
+    fn start(&mut self) -> Option<Sending> {
+        if self.pending < self.batch || self.retry_at.is_some_and(|at| Instant::now() < at) {
+            return None;
+        }
+        if self.buffer.is_empty() {
+            self.delivered();
+            return None;
+        }
+        let mut transport = self.transport.take()?;
+        let count = self.buffer.len().min(MAX_LINES_PER_WRITE);
+        let lines: Vec<String> = self.buffer.drain(..count).collect();
+        Some(Box::pin(async move {
+            let result = match tokio::time::timeout(SEND_TIMEOUT, transport.send(&lines)).await {
+                Ok(result) => result,
+                Err(_) => Err(io::Error::new(io::ErrorKind::TimedOut, "timed out")),
+            };
+            (transport, lines, result)
+        }))
+    }
+
+    fn finish(&mut self, mut transport: Transport, lines: Vec<String>, result: io::Result<()>) {
+        match result {
+            Ok(()) => {
+                self.transport = Some(transport);
+                self.sent += lines.len();
+                if self.buffer.is_empty() {
+                    self.delivered();
+                }
+            }
+            Err(err) => {
+                transport.reset();
+                self.transport = Some(transport);
+                for line in lines.into_iter().rev() {
+                    if self.buffer.len() == self.capacity {
+                        self.dropped += 1;
+                    } else {
+                        self.buffer.push_front(line);
+                    }
+                }
+                eprintln!(
+                    "systemcli: {} {}: {}; retrying in {}s with {} lines buffered{}",
+                    self.protocol.name(),
+                    self.target,
+                    err,
+                    self.backoff.as_secs(),
+                    self.buffer.len(),
+                    match self.dropped {
+                        0 => String::new(),
+                        n => format!(", {} dropped", n),
+                    }
+                );
+                self.retry_at = Some(Instant::now() + self.backoff);
+                self.backoff = (self.backoff * 2).min(MAX_BACKOFF);
+            }
+        }
+    }
+
+    fn delivered(&mut self) {
+        if self.retry_at.is_some() {
+            eprintln!(
+                "systemcli: {} {}: recovered, sent {} buffered lines",
+                self.protocol.name(),
+                self.target,
+                self.sent
+            );
+        }
+        self.pending = 0;
+        self.dropped = 0;
+        self.sent = 0;
+        self.backoff = MIN_BACKOFF;
+        self.retry_at = None;
+    }
+
+    async fn run(mut self, mut snapshots: watch::Receiver<Arc<Snapshot>>) {
+        snapshots.borrow_and_update();
+        let mut first = true;
+        let mut sending: Option<Sending> = None;
+        loop {
+            if sending.is_none() {
+                sending = self.start();
+            }
+            let retry_at = self.retry_at.filter(|at| *at > Instant::now());
+            tokio::select! {
+                changed = snapshots.changed() => {
+                    if changed.is_err() {
+                        break;
+                    }
+                    let snapshot = snapshots.borrow_and_update().clone();
+                    if first {
+                        first = false;
+                        continue;
+                    }
+                    let time = SystemTime::now()
+                        .duration_since(UNIX_EPOCH)
+                        .unwrap_or_default();
+                    self.push(&points(&snapshot), time);
+                }
+                (transport, lines, result) = async {
+                    match sending.as_mut() {
+                        Some(send) => send.await,
+                        None => std::future::pending().await,
+                    }
+                } => {
+                    sending = None;
+                    self.finish(transport, lines, result);
+                }
+                _ = async {
+                    match retry_at {
+                        Some(at) => tokio::time::sleep_until(at).await,
+                        None => std::future::pending().await,
+                    }
+                }, if sending.is_none() => {}
+            }
+        }
+    }
+}
+
+pub async fn run(config: Config, args: ExportArgs) -> Result<(), Box<dyn Error>> {
+    let mut tags = args.tags.clone();
+    if !tags.iter().any(|(key, _)| key == "host") {
+        let hostname = System::host_name().unwrap_or_else(|| "unknown".to_string());
+        tags.insert(0, ("host".to_string(), hostname));
+    }
+
+    let mut exporters = Vec::new();
+    if let Some(target) = &args.influx {
+        exporters.push(Exporter::new(
+            Protocol::Influx,
+            target,
+            args.influx_token.clone(),
+            &args,
+            &tags,
+        ));
+    }
+    if let Some(target) = &args.graphite {
+        exporters.push(Exporter::new(
+            Protocol::Graphite,
+            target,
+            None,
+            &args,
+            &tags,
+        ));
+    }
+
+    let (_refresh, refresh_rx) = watch::channel(config.refresh.clone());
+    let (_demand, demand_rx) = watch::channel(Source::ALL.into_iter().collect());
+    let snapshots = collector::spawn(refresh_rx, demand_rx);
+
+    let mut tasks = JoinSet::new();
+    for exporter in exporters {
+        eprintln!(
+            "systemcli: exporting to {} {}",
+            exporter.protocol.name(),
+            exporter.target
+        );
+        tasks.spawn(exporter.run(snapshots.clone()));
+    }
+    while let Some(result) = tasks.join_next().await {
+        result?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::{
+        io::{AsyncBufReadExt, BufReader},
+        net::TcpListener,
+    };
+
+    const TIME: Duration = Duration::from_secs(1_700_000_000);
+
+    fn args(batch: u64, buffer: usize) -> ExportArgs {
+        ExportArgs {
+            influx: None,
+            influx_token: None,
+            graphite: None,
+            prefix: "systemcli".to_string(),
+            tags: Vec::new(),
+            batch,
+            buffer,
+        }
+    }
+
+    fn tags() -> Vec<(String, String)> {
+        vec![
+            ("host".to_string(), "web 1".to_string()),
+            ("dc".to_string(), "eu,1".to_string()),
+        ]
+    }
+
+    fn point(value: f64) -> Point {
+        Point {
+            measurement: "m",
+            tags: Vec::new(),
+            fields: vec![("v", value)],
+        }
+    }
+
+    fn graphite(addr: &str, batch: u64, buffer: usize) -> Exporter {
+        Exporter::new(
+            Protocol::Graphite,
+            &Target::Tcp(addr.to_string()),
+            None,
+            &args(batch, buffer),
+            &[],
+        )
+    }
+
+    async fn read_lines(listener: &TcpListener, count: usize) -> Vec<String> {
+        let (stream, _) = tokio::time::timeout(Duration::from_secs(5), listener.accept())
+            .await
+            .unwrap()
+            .unwrap();
+        let mut reader = BufReader::new(stream).lines();
+        let mut lines = Vec::new();
+        while lines.len() < count {
+            let line = tokio::time::timeout(Duration::from_secs(5), reader.next_line())
+                .await
+                .unwrap()
+                .unwrap()
+                .unwrap();
+            lines.push(line);
+        }
+        lines
+    }
+
+    async fn flush(exporter: &mut Exporter) {
+        while let Some(sending) = exporter.start() {
+            let (transport, lines, result) = sending.await;
+            exporter.finish(transport, lines, result);
+        }
+    }
+
+    async fn read_request(stream: &mut TcpStream) -> String {
+        let mut request = Vec::new();
+        let mut chunk = [0; 1024];
+        loop {
+            let text = String::from_utf8_lossy(&request).into_owned();
+            if let Some((head, body)) = text.split_once("\r\n\r\n") {
+                let length = head
+                    .lines()
+                    .find_map(|l| l.strip_prefix("Content-Length: "))
+                    .unwrap()
+                    .parse::<usize>()
+                    .unwrap();
+                if body.len() == length {
+                    return text;
+                }
+            }
+            let n = stream.read(&mut chunk).await.unwrap();
+            assert!(n > 0);
+            request.extend_from_slice(&chunk[..n]);
+        }
+    }
+
+    fn threads(value: usize) -> Arc<Snapshot> {
+        let mut snapshot = Snapshot::default();
+        snapshot.host.threads = value;
+        Arc::new(snapshot)
+    }
+
+    async fn refused_addr() -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        listener.local_addr().unwrap().to_string()
+    }
+
+    #[test]
+    fn influx_escapes_and_sorts_tags() {
+        let points = [Point {
+            measurement: "fs",
+            tags: vec![
+                ("mountpoint", "/mnt/my disk".to_string()),
+                ("device", "a=b".to_string()),
+                ("fstype", String::new()),
+            ],
+            fields: vec![("used", 10.0), ("used_percent", f64::NAN), ("total", 20.5)],
+        }];
+        let lines = Protocol::Influx.encode(&points, "sys cli", &tags(), TIME);
+        assert_eq!(
+            lines,
+            [
+                "sys\\ cli_fs,dc=eu\\,1,device=a\\=b,host=web\\ 1,mountpoint=/mnt/my\\ disk used=10,total=20.5 1700000000000000000"
+            ]
+        );
+    }
+
+    #[test]
+    fn influx_skips_points_without_finite_fields() {
+        let points = [point(f64::NAN), point(f64::INFINITY)];
+        assert!(Protocol::Influx.encode(&points, "", &[], TIME).is_empty());
+        let lines = Protocol::Influx.encode(&[point(1.5)], "", &[], TIME);
+        assert_eq!(lines, ["m v=1.5 1700000000000000000"]);
+    }
+
+    #[test]
+    fn graphite_builds_paths_and_tag_suffix() {
+        let points = [
+            Point {
+                measurement: "fs",
+                tags: vec![
+                    ("mountpoint", "/".to_string()),
+                    ("device", "/dev/sda1".to_string()),
+                ],
+                fields: vec![("used", 10.0), ("used_percent", f64::NAN)],
+            },
+            Point {
+                measurement: "net",
+                tags: vec![("interface", "eth0.100".to_string())],
+                fields: vec![("rx_bytes", 5.0)],
+            },
+        ];
+        let lines = Protocol::Graphite.encode(&points, "servers.systemcli", &tags(), TIME);
+        assert_eq!(
+            lines,
+            [
+                "servers.systemcli.fs.root.dev_sda1.used;host=web_1;dc=eu_1 10 1700000000",
+                "servers.systemcli.net.eth0_100.rx_bytes;host=web_1;dc=eu_1 5 1700000000",
+            ]
+        );
+        let lines = Protocol::Graphite.encode(&[point(1.0)], "", &[], TIME);
+        assert_eq!(lines, ["m.v 1 1700000000"]);
+    }
+
+    #[test]
+    fn missing_swap_percent_is_not_exported() {
+        let lines = Protocol::Influx.encode(&points(&Snapshot::default()), "", &[], TIME);
+        let swap = lines.iter().find(|l| l.starts_with("swap ")).unwrap();
+        assert_eq!(swap, "swap total=0,used=0 1700000000000000000");
+    }
+
+    #[tokio::test]
+    async fn sends_influx_over_udp() {
+        let socket = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let target = Target::Udp(socket.local_addr().unwrap().to_string());
+        let mut exporter = Exporter::new(Protocol::Influx, &target, None, &args(1, 100), &[]);
+        exporter.push(&[point(1.0), point(2.0)], TIME);
+        flush(&mut exporter).await;
+        let mut buf = [0; 2048];
+        let n = tokio::time::timeout(Duration::from_secs(5), socket.recv(&mut buf))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            std::str::from_utf8(&buf[..n]).unwrap(),
+            "systemcli_m v=1 1700000000000000000\nsystemcli_m v=2 1700000000000000000\n"
+        );
+        assert!(exporter.buffer.is_empty());
+    }
+
+    #[tokio::test]
+    async fn sends_influx_over_http() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let target = Target::Http {
+            host: "127.0.0.1".to_string(),
+            port: listener.local_addr().unwrap().port(),
+            path: "/write?db=test".to_string(),
+        };
+        let server = tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut request = Vec::new();
+            let mut chunk = [0; 1024];
+            while !String::from_utf8_lossy(&request).ends_with(" 1700000000000000000\n") {
+                let n = stream.read(&mut chunk).await.unwrap();
+                assert!(n > 0);
+                request.extend_from_slice(&chunk[..n]);
+            }
+            stream
+                .write_all(b"HTTP/1.1 204 No Content\r\nContent-Length: 0\r\n\r\n")
+                .await
+                .unwrap();
+            String::from_utf8(request).unwrap()
+        });
+        let mut exporter = Exporter::new(
+            Protocol::Influx,
+            &target,
+            Some("secret".to_string()),
+            &args(1, 100),
+            &[],
+        );
+        exporter.push(&[point(1.0)], TIME);
+        flush(&mut exporter).await;
+        let request = server.await.unwrap();
+        assert!(request.starts_with("POST /write?db=test HTTP/1.1\r\n"));
+        assert!(request.contains("Authorization: Token secret\r\n"));
+        assert!(request.ends_with("\r\n\r\nsystemcli_m v=1 1700000000000000000\n"));
+        assert!(exporter.buffer.is_empty());
+        assert!(exporter.retry_at.is_none());
+    }
+
+    #[tokio::test]
+    async fn waits_for_a_full_batch() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let mut exporter = graphite(&listener.local_addr().unwrap().to_string(), 3, 100);
+        for value in [1.0, 2.0] {
+            exporter.push(&[point(value)], TIME);
+            flush(&mut exporter).await;
+        }
+        assert_eq!(exporter.buffer.len(), 2);
+        assert!(
+            tokio::time::timeout(Duration::from_millis(100), listener.accept())
+                .await
+                .is_err()
+        );
+        exporter.push(&[point(3.0)], TIME);
+        flush(&mut exporter).await;
+        assert!(exporter.buffer.is_empty());
+        assert_eq!(exporter.pending, 0);
+        assert_eq!(
+            read_lines(&listener, 3).await,
+            [
+                "systemcli.m.v 1 1700000000",
+                "systemcli.m.v 2 1700000000",
+                "systemcli.m.v 3 1700000000",
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn retries_with_backoff_after_refused_connection() {
+        let addr = refused_addr().await;
+        let mut exporter = graphite(&addr, 1, 100);
+        exporter.push(&[point(1.0)], TIME);
+        flush(&mut exporter).await;
+        let retry_at = exporter.retry_at.unwrap();
+        assert!(retry_at > Instant::now());
+        assert_eq!(exporter.backoff, MIN_BACKOFF * 2);
+        assert_eq!(exporter.buffer.len(), 1);
+
+        exporter.push(&[point(2.0)], TIME);
+        flush(&mut exporter).await;
+        assert_eq!(exporter.retry_at, Some(retry_at));
+        assert_eq!(exporter.backoff, MIN_BACKOFF * 2);
+
+        let mut expected = MIN_BACKOFF * 2;
+        for _ in 0..8 {
+            exporter.retry_at = Some(Instant::now());
+            flush(&mut exporter).await;
+            expected = (expected * 2).min(MAX_BACKOFF);
+            assert_eq!(exporter.backoff, expected);
+        }
+        assert_eq!(exporter.backoff, MAX_BACKOFF);
+        assert_eq!(exporter.buffer.len(), 2);
+
+        let listener = TcpListener::bind(&addr).await.unwrap();
+        exporter.retry_at = Some(Instant::now());
+        flush(&mut exporter).await;
+        assert!(exporter.retry_at.is_none());
+        assert_eq!(exporter.backoff, MIN_BACKOFF);
+        assert!(exporter.buffer.is_empty());
+        assert_eq!(
+            read_lines(&listener, 2).await,
+            ["systemcli.m.v 1 1700000000", "systemcli.m.v 2 1700000000"]
+        );
+    }
+
+    #[tokio::test]
+    async fn bounded_buffer_drops_oldest_lines() {
+        let addr = refused_addr().await;
+        let mut exporter = graphite(&addr, 1, 3);
+        for value in 1..=5 {
+            exporter.push(&[point(value as f64)], TIME);
+            exporter.retry_at = None;
+            flush(&mut exporter).await;
+        }
+        assert_eq!(exporter.dropped, 2);
+        assert_eq!(
+            exporter.buffer,
+            [
+                "systemcli.m.v 3 1700000000",
+                "systemcli.m.v 4 1700000000",
+                "systemcli.m.v 5 1700000000",
+            ]
+        );
+
+        let listener = TcpListener::bind(&addr).await.unwrap();
+        exporter.retry_at = None;
+        flush(&mut exporter).await;
+        assert_eq!(exporter.dropped, 0);
+        assert_eq!(
+            read_lines(&listener, 3).await,
+            [
+                "systemcli.m.v 3 1700000000",
+                "systemcli.m.v 4 1700000000",
+                "systemcli.m.v 5 1700000000",
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn skips_first_snapshot() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let exporter = graphite(&listener.local_addr().unwrap().to_string(), 1, 1000);
+        let (tx, rx) = watch::channel(Arc::new(Snapshot::default()));
+        let task = tokio::spawn(exporter.run(rx));
+        tokio::task::yield_now().await;
+
+        let mut first = Snapshot::default();
+        first.host.threads = 1;
+        tx.send(Arc::new(first)).unwrap();
+        assert!(
+            tokio::time::timeout(Duration::from_millis(100), listener.accept())
+                .await
+                .is_err()
+        );
+
+        let mut second = Snapshot::default();
+        second.host.threads = 2;
+        tx.send(Arc::new(second)).unwrap();
+        let lines = read_lines(&listener, 14).await;
+        assert!(
+            lines
+                .iter()
+                .any(|l| l.starts_with("systemcli.system.threads 2 "))
+        );
+        assert!(
+            !lines
+                .iter()
+                .any(|l| l.starts_with("systemcli.system.threads 1 "))
+        );
+        drop(tx);
+        task.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn keeps_sampling_while_a_send_is_stalled() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let target = Target::Http {
+            host: "127.0.0.1".to_string(),
+            port: listener.local_addr().unwrap().port(),
+            path: "/write".to_string(),
+        };
+        let (stalled_tx, stalled) = tokio::sync::oneshot::channel();
+        let (release, release_rx) = tokio::sync::oneshot::channel::<()>();
+        let server = tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            read_request(&mut stream).await;
+            stalled_tx.send(()).unwrap();
+            release_rx.await.unwrap();
+            stream
+                .write_all(b"HTTP/1.1 204 No Content\r\nContent-Length: 0\r\n\r\n")
+                .await
+                .unwrap();
+            drop(stream);
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let request = read_request(&mut stream).await;
+            stream
+                .write_all(b"HTTP/1.1 204 No Content\r\nContent-Length: 0\r\n\r\n")
+                .await
+                .unwrap();
+            request
+        });
+        let exporter = Exporter::new(Protocol::Influx, &target, None, &args(1, 1000), &[]);
+        let (tx, rx) = watch::channel(Arc::new(Snapshot::default()));
+        let task = tokio::spawn(exporter.run(rx));
+        tokio::task::yield_now().await;
+
+        tx.send(threads(0)).unwrap();
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        tx.send(threads(1)).unwrap();
+        tokio::time::timeout(Duration::from_secs(5), stalled)
+            .await
+            .unwrap()
+            .unwrap();
+        for value in [2, 3] {
+            tx.send(threads(value)).unwrap();
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+        release.send(()).unwrap();
+
+        let request = tokio::time::timeout(Duration::from_secs(5), server)
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(request.contains(",threads=2 "));
+        assert!(request.contains(",threads=3 "));
+        drop(tx);
+        task.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn resends_lines_after_the_peer_closes() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let mut exporter = graphite(&listener.local_addr().unwrap().to_string(), 1, 100);
+        exporter.push(&[point(1.0)], TIME);
+        flush(&mut exporter).await;
+        let (stream, _) = tokio::time::timeout(Duration::from_secs(5), listener.accept())
+            .await
+            .unwrap()
+            .unwrap();
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        drop(stream);
+        tokio::time::sleep(Duration::from_millis(50)).await;
+
+        exporter.push(&[point(2.0)], TIME);
+        flush(&mut exporter).await;
+        assert!(exporter.retry_at.is_none());
+        assert_eq!(
+            read_lines(&listener, 2).await,
+            ["systemcli.m.v 1 1700000000", "systemcli.m.v 2 1700000000"]
+        );
+    }
+}
@@ -0,0 +1,114 @@
+use clap::ValueEnum;
+use serde::Deserialize;
+
+const BYTE_UNITS: [&str; 6] = ["B", "KiB", "MiB", "GiB", "TiB", "PiB"];
+
+pub fn bytes(value: u64) -> String {
+    let mut value = value as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < BYTE_UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} {}", value as u64, BYTE_UNITS[0])
+    } else {
+        format!("{:.1} {}", value, BYTE_UNITS[unit])
+    }
+}
+
+pub struct LocalTime {
+    pub year: i32,
+    pub month: u32,
+    pub day: u32,
+    pub hour: u32,
+    pub minute: u32,
+    pub second: u32,
+}
+
+pub fn local_time(epoch_secs: u64) -> LocalTime {
+    let time = epoch_secs as libc::time_t;
+    let mut tm: libc::tm = unsafe { std::mem::zeroed() };
+    unsafe { libc::localtime_r(&time, &mut tm) };
+    LocalTime {
+        year: tm.tm_year + 1900,
+        month: (tm.tm_mon + 1) as u32,
+        day: tm.tm_mday as u32,
+        hour: tm.tm_hour as u32,
+        minute: tm.tm_min as u32,
+        second: tm.tm_sec as u32,
+    }
+}
+
+pub fn now() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+pub fn clock(epoch_secs: u64) -> String {
+    let t = local_time(epoch_secs);
+    format!("{:02}:{:02}:{:02}", t.hour, t.minute, t.second)
+}
+
+pub fn start_time(epoch_secs: u64) -> String {
+    let start = local_time(epoch_secs);
+    if now().saturating_sub(epoch_secs) < 24 * 60 * 60 {
+        format!("{:02}:{:02}", start.hour, start.minute)
+    } else {
+        format!("{:04}-{:02}-{:02}", start.year, start.month, start.day)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum RateUnit {
+    Bytes,
+    Bits,
+}
+
+impl RateUnit {
+    pub fn toggle(self) -> Self {
+        match self {
+            RateUnit::Bytes => RateUnit::Bits,
+            RateUnit::Bits => RateUnit::Bytes,
+        }
+    }
+}
+
+pub fn rate(bytes_per_sec: f64, unit: RateUnit) -> String {
+    let (mut value, units) = match unit {
+        RateUnit::Bytes => (bytes_per_sec, ["B/s", "KB/s", "MB/s", "GB/s", "TB/s"]),
+        RateUnit::Bits => (bytes_per_sec * 8.0, ["b/s", "Kb/s", "Mb/s", "Gb/s", "Tb/s"]),
+    };
+    let mut index = 0;
+    while value >= 1000.0 && index < units.len() - 1 {
+        value /= 1000.0;
+        index += 1;
+    }
+    if index == 0 {
+        format!("{:.0} {}", value, units[0])
+    } else {
+        format!("{:.1} {}", value, units[index])
+    }
+}
+
+pub fn datetime(epoch_secs: u64) -> String {
+    let t = local_time(epoch_secs);
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02}",
+        t.year, t.month, t.day, t.hour, t.minute
+    )
+}
+
+pub fn duration(secs: u64) -> String {
+    let days = secs / 86_400;
+    let hours = secs % 86_400 / 3_600;
+    let minutes = secs % 3_600 / 60;
+    if days > 0 {
+        format!("{}d {:02}:{:02}", days, hours, minutes)
+    } else {
+        format!("{:02}:{:02}", hours, minutes)
+    }
+}
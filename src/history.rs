@@ -0,0 +1,176 @@
+use serde::Deserialize;
+use std::{
+    collections::VecDeque,
+    time::{Duration, Instant},
+};
+use tui::{
+    Frame,
+    backend::Backend,
+    layout::Rect,
+    style::{Color, Style},
+    symbols::Marker,
+    text::Span,
+    widgets::{Axis, Block, Borders, Chart, Dataset, GraphType, Sparkline},
+};
+
+const RETENTION: Duration = Duration::from_secs(60 * 60);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
+pub enum Window {
+    #[serde(rename = "1m")]
+    OneMinute,
+    #[serde(rename = "5m")]
+    FiveMinutes,
+    #[serde(rename = "15m")]
+    FifteenMinutes,
+    #[serde(rename = "1h")]
+    OneHour,
+}
+
+impl Window {
+    pub fn duration(self) -> Duration {
+        match self {
+            Window::OneMinute => Duration::from_secs(60),
+            Window::FiveMinutes => Duration::from_secs(5 * 60),
+            Window::FifteenMinutes => Duration::from_secs(15 * 60),
+            Window::OneHour => RETENTION,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Window::OneMinute => "1m",
+            Window::FiveMinutes => "5m",
+            Window::FifteenMinutes => "15m",
+            Window::OneHour => "1h",
+        }
+    }
+
+    pub fn next(self) -> Self {
+        match self {
+            Window::OneMinute => Window::FiveMinutes,
+            Window::FiveMinutes => Window::FifteenMinutes,
+            Window::FifteenMinutes => Window::OneHour,
+            Window::OneHour => Window::OneMinute,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct History {
+    samples: VecDeque<(Instant, f64)>,
+}
+
+impl History {
+    pub fn push(&mut self, now: Instant, value: f64) {
+        self.samples.push_back((now, value));
+        while let Some(&(t, _)) = self.samples.front() {
+            if now.duration_since(t) <= RETENTION {
+                break;
+            }
+            self.samples.pop_front();
+        }
+    }
+
+    pub fn points(&self, now: Instant, window: Window) -> Vec<(f64, f64)> {
+        let window = window.duration();
+        self.samples
+            .iter()
+            .filter(|(t, _)| now.duration_since(*t) <= window)
+            .map(|(t, v)| (-now.duration_since(*t).as_secs_f64(), *v))
+            .collect()
+    }
+
+    pub fn max(&self, now: Instant, window: Window) -> f64 {
+        self.points(now, window)
+            .iter()
+            .map(|&(_, v)| v)
+            .fold(0.0, f64::max)
+    }
+}
+
+pub fn braille_supported() -> bool {
+    ["LC_ALL", "LC_CTYPE", "LANG"]
+        .iter()
+        .filter_map(|var| std::env::var(var).ok())
+        .find(|value| !value.is_empty())
+        .map(|value| {
+            let value = value.to_uppercase();
+            value.contains("UTF-8") || value.contains("UTF8")
+        })
+        .unwrap_or(false)
+}
+
+pub struct Series<'a> {
+    pub name: &'a str,
+    pub history: &'a History,
+    pub color: Color,
+}
+
+pub fn draw<B: Backend>(
+    f: &mut Frame<B>,
+    area: Rect,
+    title: &str,
+    series: &[Series],
+    max: f64,
+    max_label: String,
+    window: Window,
+) {
+    let now = Instant::now();
+    let block = Block::default()
+        .title(format!("{} ({})", title, window.label()))
+        .borders(Borders::ALL);
+
+    if area.height < 6 {
+        let Some(first) = series.first() else {
+            return;
+        };
+        let width = block.inner(area).width.max(1) as usize;
+        let span = window.duration().as_secs_f64();
+        let mut data = vec![0u64; width];
+        for (x, v) in first.history.points(now, window) {
+            let bucket = (((x + span) / span) * (width - 1) as f64).round() as usize;
+            let bucket = &mut data[bucket.min(width - 1)];
+            *bucket = (*bucket).max(v.max(0.0) as u64);
+        }
+        let sparkline = Sparkline::default()
+            .block(block)
+            .style(Style::default().fg(first.color))
+            .data(&data)
+            .max(max.max(1.0) as u64);
+        f.render_widget(sparkline, area);
+        return;
+    }
+
+    let marker = if braille_supported() {
+        Marker::Braille
+    } else {
+        Marker::Dot
+    };
+    let points: Vec<Vec<(f64, f64)>> = series
+        .iter()
+        .map(|s| s.history.points(now, window))
+        .collect();
+    let datasets = series
+        .iter()
+        .zip(&points)
+        .map(|(s, data)| {
+            Dataset::default()
+                .name(s.name)
+                .marker(marker)
+                .graph_type(GraphType::Line)
+                .style(Style::default().fg(s.color))
+                .data(data)
+        })
+        .collect();
+    let span = window.duration().as_secs_f64();
+    let chart = Chart::new(datasets)
+        .block(block)
+        .x_axis(Axis::default().bounds([-span, 0.0]))
+        .y_axis(
+            Axis::default()
+                .bounds([0.0, max.max(1.0)])
+                .labels(vec![Span::raw("0"), Span::raw(max_label)]),
+        );
+    f.render_widget(chart, area);
+}
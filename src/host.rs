@@ -0,0 +1,115 @@
+use crate::format;
+use std::fs;
+use sysinfo::System;
+use tui::{
+    Frame,
+    backend::Backend,
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Span, Spans},
+    widgets::Paragraph,
+};
+
+#[derive(Clone, Debug, Default)]
+pub struct HostInfo {
+    pub hostname: String,
+    pub os: String,
+    pub kernel: String,
+    pub boot_time: u64,
+    pub uptime: u64,
+    pub load: [f64; 3],
+    pub users: usize,
+    pub processes: usize,
+    pub threads: usize,
+}
+
+fn logged_in_users() -> usize {
+    let mut count = 0;
+    unsafe {
+        libc::setutxent();
+        loop {
+            let entry = libc::getutxent();
+            if entry.is_null() {
+                break;
+            }
+            if (*entry).ut_type == libc::USER_PROCESS {
+                count += 1;
+            }
+        }
+        libc::endutxent();
+    }
+    count
+}
+
+fn task_counts() -> (usize, usize) {
+    let processes = fs::read_dir("/proc")
+        .map(|entries| {
+            entries
+                .filter_map(Result::ok)
+                .filter(|e| {
+                    e.file_name()
+                        .to_string_lossy()
+                        .bytes()
+                        .all(|b| b.is_ascii_digit())
+                })
+                .count()
+        })
+        .unwrap_or(0);
+    let threads = fs::read_to_string("/proc/loadavg")
+        .ok()
+        .and_then(|text| {
+            let tasks = text.split_whitespace().nth(3)?;
+            tasks.split_once('/')?.1.parse().ok()
+        })
+        .unwrap_or(0);
+    (processes, threads)
+}
+
+pub fn collect() -> HostInfo {
+    let load = System::load_average();
+    let (processes, threads) = task_counts();
+    HostInfo {
+        hostname: System::host_name().unwrap_or_else(|| "unknown".to_string()),
+        os: System::long_os_version().unwrap_or_default(),
+        kernel: System::kernel_version().unwrap_or_default(),
+        boot_time: System::boot_time(),
+        uptime: System::uptime(),
+        load: [load.one, load.five, load.fifteen],
+        users: logged_in_users(),
+        processes,
+        threads,
+    }
+}
+
+fn field<'a>(label: &'a str, value: String) -> [Span<'a>; 2] {
+    [
+        Span::styled(label, Style::default().fg(Color::DarkGray)),
+        Span::raw(format!(" {}  ", value)),
+    ]
+}
+
+pub fn draw<B: Backend>(f: &mut Frame<B>, area: Rect, host: &HostInfo, accent: Color) {
+    let mut spans = vec![
+        Span::styled(
+            host.hostname.clone(),
+            Style::default().fg(accent).add_modifier(Modifier::BOLD),
+        ),
+        Span::raw(format!("  {} {}  ", host.os, host.kernel)),
+    ];
+    spans.extend(field("up", format::duration(host.uptime)));
+    spans.extend(field("boot", format::datetime(host.boot_time)));
+    spans.extend(field(
+        "load",
+        format!(
+            "{:.2} {:.2} {:.2}",
+            host.load[0], host.load[1], host.load[2]
+        ),
+    ));
+    spans.extend(field("users", host.users.to_string()));
+    spans.extend(field(
+        "tasks",
+        format!("{} procs, {} threads", host.processes, host.threads),
+    ));
+    spans.extend(field("time", format::clock(format::now())));
+    f.render_widget(Paragraph::new(Spans::from(spans)), area);
+}
@@ -0,0 +1,14 @@
+use crossterm::event::{self, Event};
+use tokio::sync::mpsc;
+
+pub fn spawn() -> mpsc::UnboundedReceiver<Event> {
+    let (tx, rx) = mpsc::unbounded_channel();
+    std::thread::spawn(move || {
+        while let Ok(event) = event::read() {
+            if tx.send(event).is_err() {
+                break;
+            }
+        }
+    });
+    rx
+}
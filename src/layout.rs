@@ -0,0 +1,343 @@
+use crate::planner::Source;
+use serde::{Deserialize, Deserializer, de};
+use tui::layout::{Constraint, Direction, Layout, Rect};
+
+pub const PRESETS: [&str; 6] = [
+    "full", "compact", "network", "process", "storage", "sensors",
+];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Panel {
+    Header,
+    Gauges,
+    Cores,
+    History,
+    Network,
+    Sensors,
+    Filesystems,
+    BlockIo,
+    Memory,
+    Processes,
+    Status,
+}
+
+impl Panel {
+    pub fn sources(self) -> &'static [Source] {
+        match self {
+            Panel::Header => &[Source::Host],
+            Panel::Gauges => &[Source::Cpu, Source::Memory, Source::Network],
+            Panel::Cores => &[Source::Cpu],
+            Panel::History => &[Source::Cpu, Source::Memory, Source::Network, Source::DiskIo],
+            Panel::Network => &[Source::Network],
+            Panel::Sensors => &[Source::Sensors],
+            Panel::Filesystems => &[Source::Disks],
+            Panel::BlockIo => &[Source::DiskIo],
+            Panel::Memory => &[Source::Memory],
+            Panel::Processes => &[Source::Processes],
+            Panel::Status => &[],
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Tab {
+    pub title: String,
+    pub layout: String,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Size {
+    Percent(u16),
+    Fixed(u16),
+    Min(u16),
+    Ratio(u32, u32),
+    Auto,
+}
+
+impl Size {
+    fn parse(text: &str) -> Result<Self, String> {
+        let text = text.trim();
+        let number = |s: &str| {
+            s.trim()
+                .parse::<u16>()
+                .map_err(|_| format!("invalid size `{}`", text))
+        };
+        if text == "auto" {
+            Ok(Size::Auto)
+        } else if let Some(percent) = text.strip_suffix('%') {
+            let percent = number(percent)?;
+            if percent > 100 {
+                return Err(format!("percentage `{}` is above 100", text));
+            }
+            Ok(Size::Percent(percent))
+        } else if let Some(min) = text.strip_prefix("min:") {
+            Ok(Size::Min(number(min)?))
+        } else if let Some((num, den)) = text.split_once('/') {
+            let (num, den) = (number(num)? as u32, number(den)? as u32);
+            if den == 0 || num > den {
+                return Err(format!("ratio `{}` must be between 0 and 1", text));
+            }
+            Ok(Size::Ratio(num, den))
+        } else {
+            Ok(Size::Fixed(number(text)?))
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Size {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Raw {
+            Fixed(u16),
+            Text(String),
+        }
+        match Raw::deserialize(deserializer)? {
+            Raw::Fixed(rows) => Ok(Size::Fixed(rows)),
+            Raw::Text(text) => Size::parse(&text).map_err(de::Error::custom),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Axis {
+    Rows,
+    Columns,
+}
+
+impl Axis {
+    fn direction(self) -> Direction {
+        match self {
+            Axis::Rows => Direction::Vertical,
+            Axis::Columns => Direction::Horizontal,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Content {
+    Panel(Panel),
+    Split(Axis, Vec<Node>),
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(try_from = "RawNode")]
+pub struct Node {
+    pub size: Option<Size>,
+    pub content: Content,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawNode {
+    size: Option<Size>,
+    panel: Option<Panel>,
+    rows: Option<Vec<Node>>,
+    columns: Option<Vec<Node>>,
+}
+
+impl TryFrom<RawNode> for Node {
+    type Error = String;
+
+    fn try_from(raw: RawNode) -> Result<Self, String> {
+        let content = match (raw.panel, raw.rows, raw.columns) {
+            (Some(panel), None, None) => Content::Panel(panel),
+            (None, Some(children), None) => Content::Split(Axis::Rows, children),
+            (None, None, Some(children)) => Content::Split(Axis::Columns, children),
+            _ => return Err("expected exactly one of `panel`, `rows` or `columns`".to_string()),
+        };
+        if let Content::Split(_, children) = &content
+            && children.is_empty()
+        {
+            return Err("`rows` and `columns` need at least one entry".to_string());
+        }
+        Ok(Node {
+            size: raw.size,
+            content,
+        })
+    }
+}
+
+fn panel(panel: Panel, size: Size) -> Node {
+    Node {
+        size: Some(size),
+        content: Content::Panel(panel),
+    }
+}
+
+fn split(axis: Axis, size: Size, children: Vec<Node>) -> Node {
+    Node {
+        size: Some(size),
+        content: Content::Split(axis, children),
+    }
+}
+
+pub fn full() -> Node {
+    use Axis::{Columns, Rows};
+    use Size::{Auto, Fixed, Min, Percent};
+
+    split(
+        Rows,
+        Min(0),
+        vec![
+            panel(Panel::Header, Fixed(1)),
+            split(
+                Columns,
+                Percent(35),
+                vec![
+                    panel(Panel::Gauges, Percent(30)),
+                    panel(Panel::Cores, Percent(35)),
+                    panel(Panel::History, Percent(35)),
+                ],
+            ),
+            split(
+                Columns,
+                Auto,
+                vec![
+                    panel(Panel::Network, Percent(65)),
+                    panel(Panel::Sensors, Percent(35)),
+                ],
+            ),
+            split(
+                Columns,
+                Auto,
+                vec![
+                    panel(Panel::Filesystems, Percent(40)),
+                    panel(Panel::BlockIo, Percent(30)),
+                    panel(Panel::Memory, Percent(30)),
+                ],
+            ),
+            panel(Panel::Processes, Min(0)),
+            panel(Panel::Status, Fixed(1)),
+        ],
+    )
+}
+
+pub fn preset(name: &str) -> Option<Node> {
+    use Axis::{Columns, Rows};
+    use Size::{Auto, Fixed, Min, Percent};
+
+    let children = match name {
+        "full" => return Some(full()),
+        "compact" => vec![
+            panel(Panel::Header, Fixed(1)),
+            split(
+                Columns,
+                Auto,
+                vec![
+                    panel(Panel::Gauges, Percent(35)),
+                    panel(Panel::Cores, Percent(65)),
+                ],
+            ),
+            panel(Panel::Processes, Min(0)),
+            panel(Panel::Status, Fixed(1)),
+        ],
+        "network" => vec![
+            panel(Panel::Header, Fixed(1)),
+            split(
+                Columns,
+                Auto,
+                vec![
+                    panel(Panel::Gauges, Percent(30)),
+                    panel(Panel::History, Percent(70)),
+                ],
+            ),
+            panel(Panel::Network, Min(6)),
+            panel(Panel::Processes, Percent(25)),
+            panel(Panel::Status, Fixed(1)),
+        ],
+        "process" => vec![
+            panel(Panel::Header, Fixed(1)),
+            panel(Panel::Cores, Percent(20)),
+            panel(Panel::Processes, Min(0)),
+            panel(Panel::Status, Fixed(1)),
+        ],
+        "storage" => vec![
+            panel(Panel::Header, Fixed(1)),
+            panel(Panel::Filesystems, Min(8)),
+            split(
+                Columns,
+                Auto,
+                vec![
+                    panel(Panel::BlockIo, Percent(60)),
+                    panel(Panel::Memory, Percent(40)),
+                ],
+            ),
+            panel(Panel::Status, Fixed(1)),
+        ],
+        "sensors" => vec![
+            panel(Panel::Header, Fixed(1)),
+            panel(Panel::Sensors, Min(0)),
+            panel(Panel::Status, Fixed(1)),
+        ],
+        _ => return None,
+    };
+    Some(split(Rows, Min(0), children))
+}
+
+impl Node {
+    pub fn panels(&self) -> Vec<Panel> {
+        match &self.content {
+            Content::Panel(panel) => vec![*panel],
+            Content::Split(_, children) => children.iter().flat_map(Node::panels).collect(),
+        }
+    }
+
+    fn natural(&self, height: &dyn Fn(Panel) -> u16) -> u16 {
+        match &self.content {
+            Content::Panel(panel) => height(*panel),
+            Content::Split(Axis::Rows, children) => {
+                children.iter().map(|c| c.natural(height)).sum()
+            }
+            Content::Split(Axis::Columns, children) => children
+                .iter()
+                .map(|c| c.natural(height))
+                .max()
+                .unwrap_or(0),
+        }
+    }
+
+    fn constraint(&self, axis: Axis, siblings: usize, height: &dyn Fn(Panel) -> u16) -> Constraint {
+        match self.size {
+            Some(Size::Percent(p)) => Constraint::Percentage(p),
+            Some(Size::Fixed(n)) => Constraint::Length(n),
+            Some(Size::Min(n)) => Constraint::Min(n),
+            Some(Size::Ratio(num, den)) => Constraint::Ratio(num, den),
+            Some(Size::Auto) if axis == Axis::Rows => Constraint::Length(self.natural(height)),
+            Some(Size::Auto) | None => Constraint::Ratio(1, siblings as u32),
+        }
+    }
+
+    pub fn place(&self, area: Rect, height: &dyn Fn(Panel) -> u16) -> Vec<(Panel, Rect)> {
+        let mut placed = Vec::new();
+        self.place_into(area, height, &mut placed);
+        placed
+    }
+
+    fn place_into(
+        &self,
+        area: Rect,
+        height: &dyn Fn(Panel) -> u16,
+        placed: &mut Vec<(Panel, Rect)>,
+    ) {
+        match &self.content {
+            Content::Panel(panel) => placed.push((*panel, area)),
+            Content::Split(axis, children) => {
+                let constraints: Vec<Constraint> = children
+                    .iter()
+                    .map(|c| c.constraint(*axis, children.len(), height))
+                    .collect();
+                let areas = Layout::default()
+                    .direction(axis.direction())
+                    .constraints(constraints)
+                    .split(area);
+                for (child, area) in children.iter().zip(areas) {
+                    if area.width > 0 && area.height > 0 {
+                        child.place_into(area, height, placed);
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,154 @@
+use crate::format;
+use std::{collections::HashMap, fs};
+use sysinfo::System;
+use tui::{
+    Frame,
+    backend::Backend,
+    layout::Rect,
+    style::{Color, Style},
+    text::{Span, Spans},
+    widgets::{Block, Borders, Paragraph},
+};
+
+#[derive(Clone, Debug, Default)]
+pub struct MemorySample {
+    pub total: u64,
+    pub used: u64,
+    pub available: u64,
+    pub swap_total: u64,
+    pub swap_used: u64,
+    pub buffers: u64,
+    pub cached: u64,
+    pub shared: u64,
+    pub dirty: u64,
+    pub slab: u64,
+    pub hugepages_total: u64,
+    pub hugepages_free: u64,
+    pub hugepage_size: u64,
+}
+
+impl MemorySample {
+    pub fn used_percent(&self) -> Option<f64> {
+        percent(self.used, self.total)
+    }
+
+    pub fn swap_percent(&self) -> Option<f64> {
+        percent(self.swap_used, self.swap_total)
+    }
+}
+
+pub fn percent(used: u64, total: u64) -> Option<f64> {
+    (total > 0).then(|| (used.min(total) as f64 / total as f64 * 100.0).clamp(0.0, 100.0))
+}
+
+pub fn parse_meminfo(text: &str) -> HashMap<String, u64> {
+    text.lines()
+        .filter_map(|line| {
+            let (key, rest) = line.split_once(':')?;
+            let mut parts = rest.split_whitespace();
+            let value: u64 = parts.next()?.parse().ok()?;
+            let value = match parts.next() {
+                Some("kB") => value * 1024,
+                _ => value,
+            };
+            Some((key.trim().to_string(), value))
+        })
+        .collect()
+}
+
+pub fn collect(sys: &System) -> MemorySample {
+    let meminfo = fs::read_to_string("/proc/meminfo")
+        .map(|text| parse_meminfo(&text))
+        .unwrap_or_default();
+    let field = |key: &str| meminfo.get(key).copied().unwrap_or(0);
+    MemorySample {
+        total: sys.total_memory(),
+        used: sys.used_memory(),
+        available: sys.available_memory(),
+        swap_total: sys.total_swap(),
+        swap_used: sys.used_swap(),
+        buffers: field("Buffers"),
+        cached: field("Cached"),
+        shared: field("Shmem"),
+        dirty: field("Dirty"),
+        slab: field("Slab"),
+        hugepages_total: field("HugePages_Total"),
+        hugepages_free: field("HugePages_Free"),
+        hugepage_size: field("Hugepagesize"),
+    }
+}
+
+pub fn describe_percent(percent: Option<f64>) -> String {
+    percent
+        .map(|p| format!("{:.1}%", p))
+        .unwrap_or_else(|| "n/a".to_string())
+}
+
+fn entry<'a>(label: &'a str, value: String) -> Vec<Span<'a>> {
+    vec![
+        Span::styled(label, Style::default().fg(Color::DarkGray)),
+        Span::raw(format!(" {:<12}", value)),
+    ]
+}
+
+pub fn draw<B: Backend>(f: &mut Frame<B>, area: Rect, memory: &MemorySample) {
+    let hugepages = if memory.hugepages_total == 0 {
+        "none".to_string()
+    } else {
+        format!(
+            "{}/{} x {}",
+            memory.hugepages_total - memory.hugepages_free.min(memory.hugepages_total),
+            memory.hugepages_total,
+            format::bytes(memory.hugepage_size)
+        )
+    };
+    let lines = vec![
+        Spans::from(
+            [
+                entry(
+                    "RAM",
+                    format!(
+                        "{}/{} {}",
+                        format::bytes(memory.used),
+                        format::bytes(memory.total),
+                        describe_percent(memory.used_percent())
+                    ),
+                ),
+                entry("  Avail", format::bytes(memory.available)),
+            ]
+            .concat(),
+        ),
+        Spans::from(entry(
+            "Swap",
+            format!(
+                "{}/{} {}",
+                format::bytes(memory.swap_used),
+                format::bytes(memory.swap_total),
+                describe_percent(memory.swap_percent())
+            ),
+        )),
+        Spans::from(
+            [
+                entry("Buffers", format::bytes(memory.buffers)),
+                entry("Cached", format::bytes(memory.cached)),
+            ]
+            .concat(),
+        ),
+        Spans::from(
+            [
+                entry("Shared", format::bytes(memory.shared)),
+                entry("Dirty", format::bytes(memory.dirty)),
+            ]
+            .concat(),
+        ),
+        Spans::from(
+            [
+                entry("Slab", format::bytes(memory.slab)),
+                entry("HugePages", hugepages),
+            ]
+            .concat(),
+        ),
+    ];
+    let panel = Paragraph::new(lines).block(Block::default().title("Memory").borders(Borders::ALL));
+    f.render_widget(panel, area);
+}
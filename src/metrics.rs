@@ -0,0 +1,140 @@
+use crate::{
+    collector::Snapshot,
+    network::{self, InterfaceFilter},
+};
+
+pub fn flatten(snapshot: &Snapshot, filter: &InterfaceFilter) -> Vec<(String, f64)> {
+    let mut metrics = vec![("cpu".to_string(), snapshot.cpu_usage as f64)];
+    for (i, core) in snapshot.cores.iter().enumerate() {
+        metrics.push((format!("cpu.{}", i), core.usage as f64));
+    }
+
+    let memory = &snapshot.memory;
+    metrics.extend([
+        ("mem".to_string(), memory.used_percent().unwrap_or(f64::NAN)),
+        ("mem.used".to_string(), memory.used as f64),
+        ("mem.total".to_string(), memory.total as f64),
+        ("mem.available".to_string(), memory.available as f64),
+        (
+            "swap".to_string(),
+            memory.swap_percent().unwrap_or(f64::NAN),
+        ),
+        ("swap.used".to_string(), memory.swap_used as f64),
+        ("swap.total".to_string(), memory.swap_total as f64),
+    ]);
+
+    for iface in &snapshot.interfaces {
+        metrics.push((format!("net.{}.rx", iface.name), iface.rx_rate));
+        metrics.push((format!("net.{}.tx", iface.name), iface.tx_rate));
+    }
+    let throughput = network::aggregate(&snapshot.interfaces, filter);
+    metrics.push(("net.rx".to_string(), throughput.rx));
+    metrics.push(("net.tx".to_string(), throughput.tx));
+
+    let (mut read, mut write) = (0.0, 0.0);
+    for disk in &snapshot.disk_io {
+        read += disk.read_rate;
+        write += disk.write_rate;
+        metrics.push((format!("disk.{}.read", disk.name), disk.read_rate));
+        metrics.push((format!("disk.{}.write", disk.name), disk.write_rate));
+        metrics.push((format!("disk.{}.util", disk.name), disk.utilization));
+    }
+    metrics.push(("disk.read".to_string(), read));
+    metrics.push(("disk.write".to_string(), write));
+
+    for fs in &snapshot.disks {
+        metrics.push((format!("fs.{}.used", fs.mount_point), fs.used_percent()));
+    }
+
+    let host = &snapshot.host;
+    metrics.extend([
+        ("load.1".to_string(), host.load[0]),
+        ("load.5".to_string(), host.load[1]),
+        ("load.15".to_string(), host.load[2]),
+        ("uptime".to_string(), host.uptime as f64),
+        ("procs".to_string(), host.processes as f64),
+    ]);
+    for sensor in &snapshot.sensors {
+        if let Some(temp) = sensor.temperature {
+            metrics.push((format!("temp.{}", sensor.label), temp as f64));
+        }
+    }
+    metrics
+}
+
+pub fn matches(name: &str, field: &str) -> bool {
+    match field.strip_suffix(".*") {
+        Some(prefix) => name
+            .strip_prefix(prefix)
+            .is_some_and(|rest| rest.starts_with('.')),
+        None => name == field,
+    }
+}
+
+pub fn select(values: Vec<(String, f64)>, fields: &[String]) -> Vec<(String, f64)> {
+    if fields.is_empty() {
+        return values;
+    }
+    let mut selected: Vec<(String, f64)> = Vec::new();
+    for field in fields {
+        for (name, value) in values.iter().filter(|(name, _)| matches(name, field)) {
+            if !selected.iter().any(|(seen, _)| seen == name) {
+                selected.push((name.clone(), *value));
+            }
+        }
+    }
+    selected
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn values() -> Vec<(String, f64)> {
+        ["cpu", "cpu.0", "cpu.1", "cpus", "mem"]
+            .iter()
+            .enumerate()
+            .map(|(i, name)| (name.to_string(), i as f64))
+            .collect()
+    }
+
+    fn names(selected: &[(String, f64)]) -> Vec<&str> {
+        selected.iter().map(|(name, _)| name.as_str()).collect()
+    }
+
+    #[test]
+    fn unknown_percentages_are_not_zero() {
+        let values = flatten(&Snapshot::default(), &InterfaceFilter::default());
+        let value = |name: &str| values.iter().find(|(n, _)| n == name).map(|(_, v)| *v);
+        assert!(value("swap").is_some_and(f64::is_nan));
+        assert!(value("mem").is_some_and(f64::is_nan));
+        assert_eq!(value("swap.total"), Some(0.0));
+    }
+
+    #[test]
+    fn wildcard_matches_children_only() {
+        assert!(matches("cpu.0", "cpu.*"));
+        assert!(!matches("cpu", "cpu.*"));
+        assert!(!matches("cpus", "cpu.*"));
+        assert!(matches("cpu", "cpu"));
+    }
+
+    #[test]
+    fn select_keeps_field_order() {
+        let fields = ["mem".to_string(), "cpu.*".to_string()];
+        assert_eq!(names(&select(values(), &fields)), ["mem", "cpu.0", "cpu.1"]);
+        assert_eq!(select(values(), &[]).len(), 5);
+    }
+
+    #[test]
+    fn select_drops_duplicates_keeping_first() {
+        let fields = [
+            "cpu.*".to_string(),
+            "cpu.0".to_string(),
+            "cpu.1".to_string(),
+        ];
+        assert_eq!(names(&select(values(), &fields)), ["cpu.0", "cpu.1"]);
+        let fields = ["cpu.1".to_string(), "cpu.*".to_string()];
+        assert_eq!(names(&select(values(), &fields)), ["cpu.1", "cpu.0"]);
+    }
+}
@@ -0,0 +1,333 @@
+use crate::{
+    format::{self, RateUnit},
+    sampler::RateMap,
+};
+use serde::Deserialize;
+use std::{
+    fs,
+    path::Path,
+    time::{Duration, Instant},
+};
+use sysinfo::Networks;
+use tui::{
+    Frame,
+    backend::Backend,
+    layout::{Constraint, Rect},
+    style::{Color, Modifier, Style},
+    widgets::{Block, Borders, Cell, Gauge, Row, Table},
+};
+
+const PEAK_HALF_LIFE: f64 = 30.0;
+const MIN_SCALE: f64 = 1024.0;
+
+#[derive(Clone, Debug)]
+pub struct InterfaceSample {
+    pub name: String,
+    pub mac: String,
+    pub addresses: Vec<String>,
+    pub rx_rate: f64,
+    pub tx_rate: f64,
+    pub rx_total: u64,
+    pub tx_total: u64,
+    pub rx_packet_rate: f64,
+    pub tx_packet_rate: f64,
+    pub rx_errors: u64,
+    pub tx_errors: u64,
+    pub rx_dropped: u64,
+    pub tx_dropped: u64,
+    pub link_speed: Option<u64>,
+    pub is_virtual: bool,
+}
+
+pub struct Counters {
+    rx: RateMap<String>,
+    tx: RateMap<String>,
+    rx_packets: RateMap<String>,
+    tx_packets: RateMap<String>,
+}
+
+impl Counters {
+    pub fn new() -> Self {
+        Self {
+            rx: RateMap::new(),
+            tx: RateMap::new(),
+            rx_packets: RateMap::new(),
+            tx_packets: RateMap::new(),
+        }
+    }
+
+    fn sweep(&mut self) {
+        self.rx.sweep();
+        self.tx.sweep();
+        self.rx_packets.sweep();
+        self.tx_packets.sweep();
+    }
+}
+
+fn sysfs(interface: &str, file: &str) -> Option<String> {
+    fs::read_to_string(format!("/sys/class/net/{}/{}", interface, file)).ok()
+}
+
+fn sysfs_counter(interface: &str, file: &str) -> u64 {
+    sysfs(interface, file)
+        .and_then(|value| value.trim().parse().ok())
+        .unwrap_or(0)
+}
+
+pub fn link_speed(interface: &str) -> Option<u64> {
+    let mbits: i64 = sysfs(interface, "speed")?.trim().parse().ok()?;
+    (mbits > 0).then(|| mbits as u64 * 1_000_000 / 8)
+}
+
+pub fn is_virtual(interface: &str) -> bool {
+    !Path::new(&format!("/sys/class/net/{}/device", interface)).exists()
+}
+
+pub fn collect(networks: &Networks, counters: &mut Counters, now: Instant) -> Vec<InterfaceSample> {
+    let mut interfaces: Vec<InterfaceSample> = networks
+        .iter()
+        .map(|(name, data)| InterfaceSample {
+            name: name.clone(),
+            mac: if data.mac_address().is_unspecified() {
+                String::new()
+            } else {
+                data.mac_address().to_string()
+            },
+            addresses: data.ip_networks().iter().map(|ip| ip.to_string()).collect(),
+            rx_rate: counters.rx.update(name.clone(), now, data.total_received()),
+            tx_rate: counters
+                .tx
+                .update(name.clone(), now, data.total_transmitted()),
+            rx_total: data.total_received(),
+            tx_total: data.total_transmitted(),
+            rx_packet_rate: counters.rx_packets.update(
+                name.clone(),
+                now,
+                data.total_packets_received(),
+            ),
+            tx_packet_rate: counters.tx_packets.update(
+                name.clone(),
+                now,
+                data.total_packets_transmitted(),
+            ),
+            rx_errors: data.total_errors_on_received(),
+            tx_errors: data.total_errors_on_transmitted(),
+            rx_dropped: sysfs_counter(name, "statistics/rx_dropped"),
+            tx_dropped: sysfs_counter(name, "statistics/tx_dropped"),
+            link_speed: link_speed(name),
+            is_virtual: is_virtual(name),
+        })
+        .collect();
+    counters.sweep();
+    interfaces.sort_by(|a, b| a.name.cmp(&b.name));
+    interfaces
+}
+
+fn glob(pattern: &str, name: &str) -> bool {
+    match pattern.split_once('*') {
+        None => pattern == name,
+        Some((prefix, rest)) => {
+            let Some(name) = name.strip_prefix(prefix) else {
+                return false;
+            };
+            if rest.is_empty() {
+                return true;
+            }
+            (0..=name.len())
+                .filter(|&i| name.is_char_boundary(i))
+                .any(|i| glob(rest, &name[i..]))
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct InterfaceFilter {
+    pub include: Vec<String>,
+    pub exclude: Vec<String>,
+    pub hide_virtual: bool,
+}
+
+impl Default for InterfaceFilter {
+    fn default() -> Self {
+        Self {
+            include: Vec::new(),
+            exclude: vec!["lo".to_string()],
+            hide_virtual: false,
+        }
+    }
+}
+
+impl InterfaceFilter {
+    pub fn matches(&self, interface: &InterfaceSample) -> bool {
+        if !self.include.is_empty() && !self.include.iter().any(|p| glob(p, &interface.name)) {
+            return false;
+        }
+        if self.exclude.iter().any(|p| glob(p, &interface.name)) {
+            return false;
+        }
+        !(self.hide_virtual && interface.is_virtual)
+    }
+
+    fn describe(&self) -> String {
+        let mut parts = Vec::new();
+        if !self.include.is_empty() {
+            parts.push(format!("include {}", self.include.join(",")));
+        }
+        if !self.exclude.is_empty() {
+            parts.push(format!("exclude {}", self.exclude.join(",")));
+        }
+        parts.push(if self.hide_virtual {
+            "physical only".to_string()
+        } else {
+            "virtual included".to_string()
+        });
+        parts.join(", ")
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Throughput {
+    pub rx: f64,
+    pub tx: f64,
+    pub link_speed: Option<u64>,
+}
+
+pub fn aggregate(interfaces: &[InterfaceSample], filter: &InterfaceFilter) -> Throughput {
+    let mut throughput = Throughput::default();
+    for interface in interfaces.iter().filter(|i| filter.matches(i)) {
+        throughput.rx += interface.rx_rate;
+        throughput.tx += interface.tx_rate;
+        if let Some(speed) = interface.link_speed {
+            *throughput.link_speed.get_or_insert(0) += speed;
+        }
+    }
+    throughput
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct PeakScale {
+    peak: f64,
+}
+
+impl PeakScale {
+    pub fn update(&mut self, value: f64, elapsed: Duration) {
+        let decay = 0.5f64.powf(elapsed.as_secs_f64() / PEAK_HALF_LIFE);
+        self.peak = (self.peak * decay).max(value);
+    }
+
+    pub fn peak(&self) -> f64 {
+        self.peak.max(MIN_SCALE)
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Scale {
+    pub max: f64,
+    pub source: &'static str,
+}
+
+impl Scale {
+    pub fn new(link_speed: Option<u64>, peak: &PeakScale) -> Self {
+        match link_speed {
+            Some(speed) => Scale {
+                max: speed as f64,
+                source: "link",
+            },
+            None => Scale {
+                max: peak.peak(),
+                source: "peak",
+            },
+        }
+    }
+}
+
+pub fn draw_gauge<B: Backend>(
+    f: &mut Frame<B>,
+    area: Rect,
+    title: &str,
+    rate: f64,
+    scale: Scale,
+    unit: RateUnit,
+    color: Color,
+) {
+    let label = format!(
+        "{} ({} {})",
+        format::rate(rate, unit),
+        scale.source,
+        format::rate(scale.max, unit)
+    );
+    let gauge = Gauge::default()
+        .block(Block::default().title(title).borders(Borders::ALL))
+        .gauge_style(Style::default().fg(color))
+        .ratio((rate / scale.max).clamp(0.0, 1.0))
+        .label(label);
+    f.render_widget(gauge, area);
+}
+
+pub fn draw_table<B: Backend>(
+    f: &mut Frame<B>,
+    area: Rect,
+    interfaces: &[InterfaceSample],
+    filter: &InterfaceFilter,
+    unit: RateUnit,
+) {
+    let header = Row::new(vec![
+        "Interface",
+        "RX",
+        "TX",
+        "RX total",
+        "TX total",
+        "RX pkt/s",
+        "TX pkt/s",
+        "Errors",
+        "Drops",
+        "MAC",
+        "Addresses",
+    ])
+    .style(
+        Style::default()
+            .fg(Color::Yellow)
+            .add_modifier(Modifier::BOLD),
+    );
+    let rows = interfaces.iter().map(|i| {
+        let style = if filter.matches(i) {
+            Style::default()
+        } else {
+            Style::default().fg(Color::DarkGray)
+        };
+        Row::new(vec![
+            Cell::from(i.name.clone()),
+            Cell::from(format::rate(i.rx_rate, unit)),
+            Cell::from(format::rate(i.tx_rate, unit)),
+            Cell::from(format::bytes(i.rx_total)),
+            Cell::from(format::bytes(i.tx_total)),
+            Cell::from(format!("{:.0}", i.rx_packet_rate)),
+            Cell::from(format!("{:.0}", i.tx_packet_rate)),
+            Cell::from(format!("{}/{}", i.rx_errors, i.tx_errors)),
+            Cell::from(format!("{}/{}", i.rx_dropped, i.tx_dropped)),
+            Cell::from(i.mac.clone()),
+            Cell::from(i.addresses.join(" ")),
+        ])
+        .style(style)
+    });
+    let title = format!("Network - {} (v toggles virtual)", filter.describe());
+    let widths = [
+        Constraint::Length(12),
+        Constraint::Length(11),
+        Constraint::Length(11),
+        Constraint::Length(10),
+        Constraint::Length(10),
+        Constraint::Length(8),
+        Constraint::Length(8),
+        Constraint::Length(8),
+        Constraint::Length(8),
+        Constraint::Length(17),
+        Constraint::Min(10),
+    ];
+    let table = Table::new(rows)
+        .header(header)
+        .block(Block::default().title(title).borders(Borders::ALL))
+        .widths(&widths);
+    f.render_widget(table, area);
+}
@@ -0,0 +1,120 @@
+use crate::config::RefreshConfig;
+use std::{
+    collections::{HashMap, HashSet},
+    time::{Duration, Instant},
+};
+use sysinfo::{CpuRefreshKind, MemoryRefreshKind, ProcessRefreshKind, RefreshKind, UpdateKind};
+
+const HIDDEN_SLOWDOWN: u32 = 5;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Source {
+    Cpu,
+    Memory,
+    Network,
+    Disks,
+    DiskIo,
+    Host,
+    Sensors,
+    Processes,
+}
+
+impl Source {
+    pub const ALL: [Source; 8] = [
+        Source::Cpu,
+        Source::Memory,
+        Source::Network,
+        Source::Disks,
+        Source::DiskIo,
+        Source::Host,
+        Source::Sensors,
+        Source::Processes,
+    ];
+
+    fn feeds_history(self) -> bool {
+        matches!(
+            self,
+            Source::Cpu | Source::Memory | Source::Network | Source::DiskIo | Source::Sensors
+        )
+    }
+}
+
+pub struct RefreshPlanner {
+    intervals: HashMap<Source, Duration>,
+    last: HashMap<Source, Instant>,
+}
+
+impl RefreshPlanner {
+    pub fn new(refresh: &RefreshConfig) -> Self {
+        let mut planner = Self {
+            intervals: HashMap::new(),
+            last: HashMap::new(),
+        };
+        planner.configure(refresh);
+        planner
+    }
+
+    pub fn configure(&mut self, refresh: &RefreshConfig) {
+        self.intervals = Source::ALL
+            .iter()
+            .map(|&source| (source, refresh.interval(source)))
+            .collect();
+    }
+
+    pub fn reset(&mut self) {
+        self.last.clear();
+    }
+
+    pub fn tick(&self) -> Duration {
+        self.intervals
+            .values()
+            .copied()
+            .min()
+            .unwrap_or(Duration::from_secs(1))
+    }
+
+    pub fn due(&mut self, now: Instant, visible: &HashSet<Source>) -> HashSet<Source> {
+        let slack = self.tick() / 4;
+        let due: HashSet<Source> = Source::ALL
+            .into_iter()
+            .filter(|source| {
+                let interval = if visible.contains(source) {
+                    self.intervals[source]
+                } else if source.feeds_history() {
+                    self.intervals[source] * HIDDEN_SLOWDOWN
+                } else {
+                    return false;
+                };
+                match self.last.get(source) {
+                    Some(&last) => now + slack >= last + interval,
+                    None => true,
+                }
+            })
+            .collect();
+        for &source in &due {
+            self.last.insert(source, now);
+        }
+        due
+    }
+}
+
+pub fn system_refresh(due: &HashSet<Source>) -> RefreshKind {
+    let mut kind = RefreshKind::nothing();
+    if due.contains(&Source::Cpu) {
+        kind = kind.with_cpu(CpuRefreshKind::everything());
+    }
+    if due.contains(&Source::Memory) {
+        kind = kind.with_memory(MemoryRefreshKind::everything());
+    }
+    kind
+}
+
+pub fn process_refresh() -> ProcessRefreshKind {
+    ProcessRefreshKind::nothing()
+        .with_cpu()
+        .with_memory()
+        .with_disk_usage()
+        .with_user(UpdateKind::OnlyIfNotSet)
+        .with_cmd(UpdateKind::OnlyIfNotSet)
+        .without_tasks()
+}
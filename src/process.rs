@@ -0,0 +1,393 @@
+use crate::{
+    format,
+    sampler::RateMap,
+    tree::{self, ViewRow},
+};
+use std::{cmp::Ordering, collections::HashSet, time::Instant};
+use sysinfo::{System, ThreadKind, Users};
+use tui::{
+    Frame,
+    backend::Backend,
+    layout::{Constraint, Rect},
+    style::{Color, Modifier, Style},
+    widgets::{Block, Borders, Cell, Row, Table, TableState},
+};
+
+#[derive(Clone, Debug)]
+pub struct ProcessInfo {
+    pub pid: u32,
+    pub parent: Option<u32>,
+    pub name: String,
+    pub user: String,
+    pub cpu: f32,
+    pub memory: u64,
+    pub read_rate: f64,
+    pub write_rate: f64,
+    pub state: String,
+    pub start_time: u64,
+    pub command: String,
+}
+
+pub struct IoRates {
+    read: RateMap<(u32, u64)>,
+    write: RateMap<(u32, u64)>,
+}
+
+impl IoRates {
+    pub fn new() -> Self {
+        Self {
+            read: RateMap::new(),
+            write: RateMap::new(),
+        }
+    }
+}
+
+pub fn collect(sys: &System, users: &Users, io: &mut IoRates, now: Instant) -> Vec<ProcessInfo> {
+    let processes = sys
+        .processes()
+        .values()
+        .filter(|p| p.thread_kind() != Some(ThreadKind::Userland))
+        .map(|p| {
+            let name = p.name().to_string_lossy().into_owned();
+            let command = p
+                .cmd()
+                .iter()
+                .map(|arg| arg.to_string_lossy())
+                .collect::<Vec<_>>()
+                .join(" ");
+            let user = p
+                .user_id()
+                .map(|uid| {
+                    users
+                        .get_user_by_id(uid)
+                        .map(|u| u.name().to_string())
+                        .unwrap_or_else(|| uid.to_string())
+                })
+                .unwrap_or_default();
+            let key = (p.pid().as_u32(), p.start_time());
+            let disk = p.disk_usage();
+            ProcessInfo {
+                pid: p.pid().as_u32(),
+                parent: p.parent().map(|pid| pid.as_u32()),
+                command: if command.is_empty() {
+                    format!("[{}]", name)
+                } else {
+                    command
+                },
+                name,
+                user,
+                cpu: p.cpu_usage(),
+                memory: p.memory(),
+                read_rate: io.read.update(key, now, disk.total_read_bytes),
+                write_rate: io.write.update(key, now, disk.total_written_bytes),
+                state: p.status().to_string(),
+                start_time: p.start_time(),
+            }
+        })
+        .collect();
+    io.read.sweep();
+    io.write.sweep();
+    processes
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SortColumn {
+    Pid,
+    Name,
+    User,
+    Cpu,
+    Memory,
+    Read,
+    Write,
+    State,
+    StartTime,
+    Command,
+}
+
+impl SortColumn {
+    const ALL: [SortColumn; 10] = [
+        SortColumn::Pid,
+        SortColumn::Name,
+        SortColumn::User,
+        SortColumn::Cpu,
+        SortColumn::Memory,
+        SortColumn::Read,
+        SortColumn::Write,
+        SortColumn::State,
+        SortColumn::StartTime,
+        SortColumn::Command,
+    ];
+
+    pub fn title(self) -> &'static str {
+        match self {
+            SortColumn::Pid => "PID",
+            SortColumn::Name => "Name",
+            SortColumn::User => "User",
+            SortColumn::Cpu => "CPU%",
+            SortColumn::Memory => "Memory",
+            SortColumn::Read => "Read/s",
+            SortColumn::Write => "Write/s",
+            SortColumn::State => "State",
+            SortColumn::StartTime => "Start",
+            SortColumn::Command => "Command",
+        }
+    }
+
+    fn index(self) -> usize {
+        Self::ALL.iter().position(|c| *c == self).unwrap_or(0)
+    }
+
+    pub fn next(self) -> Self {
+        Self::ALL[(self.index() + 1) % Self::ALL.len()]
+    }
+
+    pub fn prev(self) -> Self {
+        Self::ALL[(self.index() + Self::ALL.len() - 1) % Self::ALL.len()]
+    }
+
+    pub fn compare(self, a: &ProcessInfo, b: &ProcessInfo) -> Ordering {
+        match self {
+            SortColumn::Pid => a.pid.cmp(&b.pid),
+            SortColumn::Name => a.name.to_lowercase().cmp(&b.name.to_lowercase()),
+            SortColumn::User => a.user.cmp(&b.user),
+            SortColumn::Cpu => a.cpu.total_cmp(&b.cpu),
+            SortColumn::Memory => a.memory.cmp(&b.memory),
+            SortColumn::Read => a.read_rate.total_cmp(&b.read_rate),
+            SortColumn::Write => a.write_rate.total_cmp(&b.write_rate),
+            SortColumn::State => a.state.cmp(&b.state),
+            SortColumn::StartTime => a.start_time.cmp(&b.start_time),
+            SortColumn::Command => a.command.cmp(&b.command),
+        }
+        .then_with(|| a.pid.cmp(&b.pid))
+    }
+}
+
+pub struct ProcessTable {
+    pub processes: Vec<ProcessInfo>,
+    pub rows: Vec<ViewRow>,
+    pub sort: SortColumn,
+    pub descending: bool,
+    pub tree: bool,
+    pub selected_pid: Option<u32>,
+    collapsed: HashSet<u32>,
+    state: TableState,
+    page: usize,
+}
+
+impl ProcessTable {
+    pub fn new() -> Self {
+        Self {
+            processes: Vec::new(),
+            rows: Vec::new(),
+            sort: SortColumn::Cpu,
+            descending: true,
+            tree: false,
+            selected_pid: None,
+            collapsed: HashSet::new(),
+            state: TableState::default(),
+            page: 10,
+        }
+    }
+
+    pub fn update(&mut self, processes: Vec<ProcessInfo>) {
+        self.processes = processes;
+        self.sort_rows();
+    }
+
+    fn sort_rows(&mut self) {
+        let sort = self.sort;
+        self.processes.sort_by(|a, b| sort.compare(a, b));
+        if self.descending {
+            self.processes.reverse();
+        }
+        self.rebuild();
+    }
+
+    fn rebuild(&mut self) {
+        self.rows = if self.tree {
+            tree::build(&self.processes, &self.collapsed)
+        } else {
+            tree::flat(&self.processes)
+        };
+        self.sync_selection();
+    }
+
+    fn pid_at(&self, row: usize) -> u32 {
+        self.processes[self.rows[row].index].pid
+    }
+
+    fn sync_selection(&mut self) {
+        let index = match self.selected_pid {
+            Some(pid) => (0..self.rows.len())
+                .find(|&row| self.pid_at(row) == pid)
+                .or_else(|| {
+                    self.state
+                        .selected()
+                        .map(|i| i.min(self.rows.len().saturating_sub(1)))
+                }),
+            None => Some(0),
+        };
+        let index = index.filter(|_| !self.rows.is_empty());
+        self.selected_pid = index.map(|i| self.pid_at(i));
+        self.state.select(index);
+    }
+
+    pub fn selected(&self) -> Option<&ProcessInfo> {
+        self.state
+            .selected()
+            .and_then(|i| self.rows.get(i))
+            .map(|row| &self.processes[row.index])
+    }
+
+    pub fn selected_subtree(&self) -> Vec<u32> {
+        self.selected()
+            .map(|p| tree::subtree(&self.processes, p.pid))
+            .unwrap_or_default()
+    }
+
+    pub fn set_sort(&mut self, sort: SortColumn) {
+        self.sort = sort;
+        self.sort_rows();
+    }
+
+    pub fn reverse(&mut self) {
+        self.descending = !self.descending;
+        self.sort_rows();
+    }
+
+    pub fn toggle_tree(&mut self) {
+        self.tree = !self.tree;
+        self.rebuild();
+    }
+
+    pub fn set_collapsed(&mut self, collapsed: Option<bool>) {
+        if !self.tree {
+            return;
+        }
+        let Some(pid) = self.selected_pid else {
+            return;
+        };
+        let collapse = collapsed.unwrap_or(!self.collapsed.contains(&pid));
+        if collapse {
+            self.collapsed.insert(pid);
+        } else {
+            self.collapsed.remove(&pid);
+        }
+        self.rebuild();
+    }
+
+    fn select_index(&mut self, index: usize) {
+        if self.rows.is_empty() {
+            return;
+        }
+        let index = index.min(self.rows.len() - 1);
+        self.selected_pid = Some(self.pid_at(index));
+        self.state.select(Some(index));
+    }
+
+    pub fn move_selection(&mut self, delta: isize) {
+        let current = self.state.selected().unwrap_or(0);
+        self.select_index(current.saturating_add_signed(delta));
+    }
+
+    pub fn page_down(&mut self) {
+        self.move_selection(self.page as isize);
+    }
+
+    pub fn page_up(&mut self) {
+        self.move_selection(-(self.page as isize));
+    }
+
+    pub fn first(&mut self) {
+        self.select_index(0);
+    }
+
+    pub fn last(&mut self) {
+        self.select_index(usize::MAX);
+    }
+
+    pub fn draw<B: Backend>(&mut self, f: &mut Frame<B>, area: Rect) {
+        let mut columns: Vec<Cell> = SortColumn::ALL
+            .iter()
+            .map(|&column| {
+                let mut title = column.title().to_string();
+                if column == self.sort {
+                    title.push(if self.descending { '▼' } else { '▲' });
+                }
+                Cell::from(title)
+            })
+            .collect();
+        if self.tree {
+            columns.insert(7, Cell::from("ΣCPU%"));
+            columns.insert(8, Cell::from("ΣMemory"));
+        }
+        let header = Row::new(columns).style(
+            Style::default()
+                .fg(Color::Yellow)
+                .add_modifier(Modifier::BOLD),
+        );
+
+        let tree = self.tree;
+        let rows = self.rows.iter().map(|row| {
+            let p = &self.processes[row.index];
+            let marker = match (row.children, row.collapsed) {
+                (0, _) => "",
+                (_, true) => "▸ ",
+                (_, false) => "▾ ",
+            };
+            let mut cells = vec![
+                Cell::from(p.pid.to_string()),
+                Cell::from(format!("{}{}{}", row.prefix, marker, p.name)),
+                Cell::from(p.user.clone()),
+                Cell::from(format!("{:.1}", p.cpu)),
+                Cell::from(format::bytes(p.memory)),
+                Cell::from(format::bytes(p.read_rate as u64)),
+                Cell::from(format::bytes(p.write_rate as u64)),
+                Cell::from(p.state.clone()),
+                Cell::from(format::start_time(p.start_time)),
+                Cell::from(p.command.clone()),
+            ];
+            if tree {
+                cells.insert(7, Cell::from(format!("{:.1}", row.total_cpu)));
+                cells.insert(8, Cell::from(format::bytes(row.total_memory)));
+            }
+            Row::new(cells)
+        });
+
+        let title = if self.tree {
+            format!(
+                "Process tree ({}) - t flat, ←/→ collapse/expand, k signal, K signal subtree",
+                self.processes.len()
+            )
+        } else {
+            format!(
+                "Processes ({}) - </> sort, r reverse, t tree, ↑/↓ select, k signal",
+                self.processes.len()
+            )
+        };
+        let mut widths = vec![
+            Constraint::Length(7),
+            Constraint::Length(if self.tree { 32 } else { 16 }),
+            Constraint::Length(10),
+            Constraint::Length(6),
+            Constraint::Length(10),
+            Constraint::Length(10),
+            Constraint::Length(10),
+            Constraint::Length(9),
+            Constraint::Length(10),
+            Constraint::Min(10),
+        ];
+        if self.tree {
+            widths.insert(7, Constraint::Length(6));
+            widths.insert(8, Constraint::Length(10));
+        }
+        let table = Table::new(rows)
+            .header(header)
+            .block(Block::default().title(title).borders(Borders::ALL))
+            .widths(&widths)
+            .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+
+        self.page = area.height.saturating_sub(3).max(1) as usize;
+        f.render_stateful_widget(table, area, &mut self.state);
+    }
+}
@@ -0,0 +1,280 @@
+use crate::collector::Snapshot;
+use std::{collections::HashMap, fmt::Write};
+
+pub const CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+fn escape(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+struct Encoder {
+    out: String,
+}
+
+impl Encoder {
+    fn family(&mut self, name: &str, kind: &str, help: &str) -> Family<'_> {
+        let _ = writeln!(self.out, "# HELP systemcli_{} {}", name, help);
+        let _ = writeln!(self.out, "# TYPE systemcli_{} {}", name, kind);
+        Family {
+            out: &mut self.out,
+            name: format!("systemcli_{}", name),
+        }
+    }
+}
+
+struct Family<'a> {
+    out: &'a mut String,
+    name: String,
+}
+
+impl Family<'_> {
+    fn sample(&mut self, labels: &[(&str, &str)], value: f64) -> &mut Self {
+        self.out.push_str(&self.name);
+        if !labels.is_empty() {
+            let labels: Vec<String> = labels
+                .iter()
+                .map(|(k, v)| format!("{}=\"{}\"", k, escape(v)))
+                .collect();
+            let _ = write!(self.out, "{{{}}}", labels.join(","));
+        }
+        let _ = writeln!(self.out, " {}", value);
+        self
+    }
+
+    fn value(&mut self, value: f64) -> &mut Self {
+        self.sample(&[], value)
+    }
+}
+
+pub fn render(snapshot: &Snapshot, top: usize) -> String {
+    let mut e = Encoder { out: String::new() };
+
+    e.family("cpu_usage_ratio", "gauge", "Total CPU usage from 0 to 1.")
+        .value(snapshot.cpu_usage as f64 / 100.0);
+    let mut family = e.family(
+        "cpu_core_usage_ratio",
+        "gauge",
+        "Per-core CPU usage from 0 to 1.",
+    );
+    for (i, core) in snapshot.cores.iter().enumerate() {
+        family.sample(&[("core", &i.to_string())], core.usage as f64 / 100.0);
+    }
+    let mut family = e.family(
+        "cpu_core_frequency_hertz",
+        "gauge",
+        "Per-core clock frequency.",
+    );
+    for (i, core) in snapshot.cores.iter().enumerate() {
+        family.sample(&[("core", &i.to_string())], core.frequency as f64 * 1e6);
+    }
+
+    let host = &snapshot.host;
+    for (i, minutes) in [1, 5, 15].into_iter().enumerate() {
+        e.family(
+            &format!("load{}", minutes),
+            "gauge",
+            &format!("{}-minute load average.", minutes),
+        )
+        .value(host.load[i]);
+    }
+    e.family(
+        "boot_time_seconds",
+        "gauge",
+        "Boot time in seconds since the epoch.",
+    )
+    .value(host.boot_time as f64);
+    e.family("processes", "gauge", "Number of processes.")
+        .value(host.processes as f64);
+    e.family("threads", "gauge", "Number of kernel scheduling entities.")
+        .value(host.threads as f64);
+
+    let memory = &snapshot.memory;
+    for (name, help, value) in [
+        ("memory_total_bytes", "Total physical memory.", memory.total),
+        ("memory_used_bytes", "Used physical memory.", memory.used),
+        (
+            "memory_available_bytes",
+            "Memory available for new work.",
+            memory.available,
+        ),
+        (
+            "memory_buffers_bytes",
+            "Memory used for block device buffers.",
+            memory.buffers,
+        ),
+        (
+            "memory_cached_bytes",
+            "Memory used by the page cache.",
+            memory.cached,
+        ),
+        ("swap_total_bytes", "Total swap space.", memory.swap_total),
+        ("swap_used_bytes", "Used swap space.", memory.swap_used),
+    ] {
+        e.family(name, "gauge", help).value(value as f64);
+    }
+
+    type InterfaceField = fn(&crate::network::InterfaceSample) -> u64;
+    let counters: [(&str, &str, InterfaceField); 6] = [
+        ("network_receive_bytes_total", "Bytes received.", |i| {
+            i.rx_total
+        }),
+        ("network_transmit_bytes_total", "Bytes transmitted.", |i| {
+            i.tx_total
+        }),
+        ("network_receive_errors_total", "Receive errors.", |i| {
+            i.rx_errors
+        }),
+        ("network_transmit_errors_total", "Transmit errors.", |i| {
+            i.tx_errors
+        }),
+        (
+            "network_receive_drop_total",
+            "Dropped received packets.",
+            |i| i.rx_dropped,
+        ),
+        (
+            "network_transmit_drop_total",
+            "Dropped transmitted packets.",
+            |i| i.tx_dropped,
+        ),
+    ];
+    for (name, help, field) in counters {
+        let mut family = e.family(name, "counter", help);
+        for iface in &snapshot.interfaces {
+            family.sample(&[("interface", &iface.name)], field(iface) as f64);
+        }
+    }
+    let mut family = e.family(
+        "network_speed_bytes",
+        "gauge",
+        "Link speed in bytes per second.",
+    );
+    for iface in &snapshot.interfaces {
+        if let Some(speed) = iface.link_speed {
+            family.sample(&[("interface", &iface.name)], speed as f64);
+        }
+    }
+
+    type DiskField = fn(&crate::diskstats::DiskIoSample) -> f64;
+    let disk: [(&str, &str, &str, DiskField); 6] = [
+        ("disk_read_bytes_total", "counter", "Bytes read.", |d| {
+            d.read_bytes as f64
+        }),
+        (
+            "disk_written_bytes_total",
+            "counter",
+            "Bytes written.",
+            |d| d.write_bytes as f64,
+        ),
+        (
+            "disk_reads_completed_total",
+            "counter",
+            "Reads completed.",
+            |d| d.reads as f64,
+        ),
+        (
+            "disk_writes_completed_total",
+            "counter",
+            "Writes completed.",
+            |d| d.writes as f64,
+        ),
+        (
+            "disk_io_time_seconds_total",
+            "counter",
+            "Time spent doing I/O.",
+            |d| d.io_ms as f64 / 1000.0,
+        ),
+        ("disk_io_now", "gauge", "I/O requests in flight.", |d| {
+            d.in_flight as f64
+        }),
+    ];
+    for (name, kind, help, field) in disk {
+        let mut family = e.family(name, kind, help);
+        for device in &snapshot.disk_io {
+            family.sample(&[("device", &device.name)], field(device));
+        }
+    }
+
+    type FsField = fn(&crate::disk::DiskSample) -> u64;
+    let filesystems: [(&str, &str, FsField); 5] = [
+        ("filesystem_size_bytes", "Filesystem size.", |d| d.total),
+        ("filesystem_used_bytes", "Used filesystem space.", |d| {
+            d.used
+        }),
+        (
+            "filesystem_avail_bytes",
+            "Space available to unprivileged users.",
+            |d| d.available,
+        ),
+        ("filesystem_files", "Total inodes.", |d| d.inodes_total),
+        ("filesystem_files_used", "Used inodes.", |d| d.inodes_used),
+    ];
+    for (name, help, field) in filesystems {
+        let mut family = e.family(name, "gauge", help);
+        for fs in &snapshot.disks {
+            family.sample(
+                &[
+                    ("device", &fs.device),
+                    ("mountpoint", &fs.mount_point),
+                    ("fstype", &fs.fs_type),
+                ],
+                field(fs) as f64,
+            );
+        }
+    }
+
+    let mut family = e.family("temperature_celsius", "gauge", "Sensor temperature.");
+    let mut seen: HashMap<&str, usize> = HashMap::new();
+    for sensor in &snapshot.sensors {
+        let index = seen.entry(&sensor.label).or_default();
+        if let Some(temp) = sensor.temperature {
+            family.sample(
+                &[("sensor", &sensor.label), ("index", &index.to_string())],
+                temp as f64,
+            );
+        }
+        *index += 1;
+    }
+
+    if top > 0 {
+        let mut processes: Vec<_> = snapshot.processes.iter().collect();
+        processes.sort_by(|a, b| b.cpu.total_cmp(&a.cpu).then(b.memory.cmp(&a.memory)));
+        processes.truncate(top);
+        let mut family = e.family(
+            "process_cpu_usage_ratio",
+            "gauge",
+            "CPU usage of the busiest processes, where 1 is one full core.",
+        );
+        for p in &processes {
+            let pid = p.pid.to_string();
+            family.sample(
+                &[("pid", &pid), ("name", &p.name), ("user", &p.user)],
+                p.cpu as f64 / 100.0,
+            );
+        }
+        let mut family = e.family(
+            "process_resident_memory_bytes",
+            "gauge",
+            "Resident memory of the busiest processes.",
+        );
+        for p in &processes {
+            let pid = p.pid.to_string();
+            family.sample(
+                &[("pid", &pid), ("name", &p.name), ("user", &p.user)],
+                p.memory as f64,
+            );
+        }
+    }
+
+    let overhead = &snapshot.overhead;
+    e.family(
+        "sample_duration_seconds",
+        "gauge",
+        "Time taken by the last collection pass.",
+    )
+    .value(overhead.sample_time.as_secs_f64());
+    e.out
+}
@@ -0,0 +1,98 @@
+use crate::config::{self, Config, ConfigError};
+use std::{
+    ffi::{CString, OsStr},
+    io,
+    os::unix::ffi::OsStrExt,
+    path::{Path, PathBuf},
+};
+use tokio::sync::mpsc;
+
+const EVENT_HEADER: usize = std::mem::size_of::<libc::inotify_event>();
+
+struct Inotify {
+    fd: libc::c_int,
+}
+
+impl Inotify {
+    fn watch(dir: &Path) -> io::Result<Self> {
+        let fd = unsafe { libc::inotify_init1(libc::IN_CLOEXEC) };
+        if fd < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        let inotify = Inotify { fd };
+        let dir = CString::new(dir.as_os_str().as_bytes())
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidInput, err))?;
+        let mask = libc::IN_CLOSE_WRITE | libc::IN_MOVED_TO;
+        if unsafe { libc::inotify_add_watch(fd, dir.as_ptr(), mask) } < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(inotify)
+    }
+
+    fn wait(&self, buf: &mut [u8], name: &OsStr) -> io::Result<bool> {
+        let len = unsafe { libc::read(self.fd, buf.as_mut_ptr().cast(), buf.len()) };
+        if len < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        let events = &buf[..len as usize];
+        let mut offset = 0;
+        let mut matched = false;
+        while offset + EVENT_HEADER <= events.len() {
+            let event: libc::inotify_event =
+                unsafe { std::ptr::read_unaligned(events[offset..].as_ptr().cast()) };
+            let start = offset + EVENT_HEADER;
+            let end = (start + event.len as usize).min(events.len());
+            let raw = &events[start..end];
+            let raw = &raw[..raw.iter().position(|&b| b == 0).unwrap_or(raw.len())];
+            matched |= OsStr::from_bytes(raw) == name;
+            offset = end;
+        }
+        Ok(matched)
+    }
+}
+
+impl Drop for Inotify {
+    fn drop(&mut self) {
+        unsafe { libc::close(self.fd) };
+    }
+}
+
+pub fn spawn(path: PathBuf) -> mpsc::UnboundedReceiver<Result<Config, ConfigError>> {
+    let (tx, rx) = mpsc::unbounded_channel();
+    std::thread::spawn(move || {
+        let (Some(dir), Some(name)) = (path.parent(), path.file_name()) else {
+            return;
+        };
+        let dir = if dir.as_os_str().is_empty() {
+            Path::new(".")
+        } else {
+            dir
+        };
+        if !dir.is_dir() {
+            return;
+        }
+        let inotify = match Inotify::watch(dir) {
+            Ok(inotify) => inotify,
+            Err(err) => {
+                let _ = tx.send(Err(ConfigError::Io(path.clone(), err)));
+                return;
+            }
+        };
+        let mut buf = vec![0u8; 4096];
+        loop {
+            match inotify.wait(&mut buf, name) {
+                Ok(false) => continue,
+                Ok(true) => {}
+                Err(err) if err.kind() == io::ErrorKind::Interrupted => continue,
+                Err(err) => {
+                    let _ = tx.send(Err(ConfigError::Io(path.clone(), err)));
+                    return;
+                }
+            }
+            if tx.send(config::read(&path)).is_err() {
+                return;
+            }
+        }
+    });
+    rx
+}
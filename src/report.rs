@@ -0,0 +1,244 @@
+use crate::{collector::Snapshot, format};
+use serde::Serialize;
+use std::time::Duration;
+
+pub const SCHEMA: &str = "systemcli.snapshot";
+pub const VERSION: u32 = 1;
+
+#[derive(Debug, Serialize)]
+pub struct Report {
+    pub schema: &'static str,
+    pub version: u32,
+    pub timestamp: u64,
+    pub interval_ms: u64,
+    pub host: Host,
+    pub cpu: Cpu,
+    pub memory: Memory,
+    pub network: Vec<Interface>,
+    pub filesystems: Vec<Filesystem>,
+    pub disks: Vec<Disk>,
+    pub sensors: Vec<Sensor>,
+    pub processes: Vec<Process>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Host {
+    pub hostname: String,
+    pub os: String,
+    pub kernel: String,
+    pub boot_time: u64,
+    pub uptime_secs: u64,
+    pub load: [f64; 3],
+    pub users: usize,
+    pub processes: usize,
+    pub threads: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Cpu {
+    pub usage_percent: f64,
+    pub cores: Vec<Core>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Core {
+    pub index: usize,
+    pub usage_percent: f64,
+    pub frequency_mhz: u64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Memory {
+    pub total_bytes: u64,
+    pub used_bytes: u64,
+    pub available_bytes: u64,
+    pub used_percent: Option<f64>,
+    pub swap_total_bytes: u64,
+    pub swap_used_bytes: u64,
+    pub swap_used_percent: Option<f64>,
+    pub buffers_bytes: u64,
+    pub cached_bytes: u64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Interface {
+    pub name: String,
+    pub rx_bytes_per_sec: f64,
+    pub tx_bytes_per_sec: f64,
+    pub rx_bytes_total: u64,
+    pub tx_bytes_total: u64,
+    pub rx_errors: u64,
+    pub tx_errors: u64,
+    pub rx_dropped: u64,
+    pub tx_dropped: u64,
+    pub link_speed_bytes_per_sec: Option<u64>,
+    pub virtual_interface: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Filesystem {
+    pub device: String,
+    pub mount_point: String,
+    pub fs_type: String,
+    pub total_bytes: u64,
+    pub used_bytes: u64,
+    pub available_bytes: u64,
+    pub used_percent: f64,
+    pub inodes_total: u64,
+    pub inodes_used: u64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Disk {
+    pub name: String,
+    pub read_bytes_per_sec: f64,
+    pub write_bytes_per_sec: f64,
+    pub read_iops: f64,
+    pub write_iops: f64,
+    pub await_ms: f64,
+    pub utilization_percent: f64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Sensor {
+    pub label: String,
+    pub celsius: Option<f32>,
+    pub max_celsius: Option<f32>,
+    pub critical_celsius: Option<f32>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Process {
+    pub pid: u32,
+    pub parent: Option<u32>,
+    pub name: String,
+    pub user: String,
+    pub cpu_percent: f32,
+    pub memory_bytes: u64,
+    pub read_bytes_per_sec: f64,
+    pub write_bytes_per_sec: f64,
+    pub state: String,
+    pub start_time: u64,
+    pub command: String,
+}
+
+pub fn build(snapshot: &Snapshot, interval: Duration, top: usize) -> Report {
+    let host = &snapshot.host;
+    let memory = &snapshot.memory;
+    let mut processes: Vec<_> = snapshot.processes.iter().collect();
+    processes.sort_by(|a, b| b.cpu.total_cmp(&a.cpu).then(b.memory.cmp(&a.memory)));
+
+    Report {
+        schema: SCHEMA,
+        version: VERSION,
+        timestamp: format::now(),
+        interval_ms: interval.as_millis() as u64,
+        host: Host {
+            hostname: host.hostname.clone(),
+            os: host.os.clone(),
+            kernel: host.kernel.clone(),
+            boot_time: host.boot_time,
+            uptime_secs: host.uptime,
+            load: host.load,
+            users: host.users,
+            processes: host.processes,
+            threads: host.threads,
+        },
+        cpu: Cpu {
+            usage_percent: snapshot.cpu_usage as f64,
+            cores: snapshot
+                .cores
+                .iter()
+                .enumerate()
+                .map(|(index, core)| Core {
+                    index,
+                    usage_percent: core.usage as f64,
+                    frequency_mhz: core.frequency,
+                })
+                .collect(),
+        },
+        memory: Memory {
+            total_bytes: memory.total,
+            used_bytes: memory.used,
+            available_bytes: memory.available,
+            used_percent: memory.used_percent(),
+            swap_total_bytes: memory.swap_total,
+            swap_used_bytes: memory.swap_used,
+            swap_used_percent: memory.swap_percent(),
+            buffers_bytes: memory.buffers,
+            cached_bytes: memory.cached,
+        },
+        network: snapshot
+            .interfaces
+            .iter()
+            .map(|i| Interface {
+                name: i.name.clone(),
+                rx_bytes_per_sec: i.rx_rate,
+                tx_bytes_per_sec: i.tx_rate,
+                rx_bytes_total: i.rx_total,
+                tx_bytes_total: i.tx_total,
+                rx_errors: i.rx_errors,
+                tx_errors: i.tx_errors,
+                rx_dropped: i.rx_dropped,
+                tx_dropped: i.tx_dropped,
+                link_speed_bytes_per_sec: i.link_speed,
+                virtual_interface: i.is_virtual,
+            })
+            .collect(),
+        filesystems: snapshot
+            .disks
+            .iter()
+            .map(|d| Filesystem {
+                device: d.device.clone(),
+                mount_point: d.mount_point.clone(),
+                fs_type: d.fs_type.clone(),
+                total_bytes: d.total,
+                used_bytes: d.used,
+                available_bytes: d.available,
+                used_percent: d.used_percent(),
+                inodes_total: d.inodes_total,
+                inodes_used: d.inodes_used,
+            })
+            .collect(),
+        disks: snapshot
+            .disk_io
+            .iter()
+            .map(|d| Disk {
+                name: d.name.clone(),
+                read_bytes_per_sec: d.read_rate,
+                write_bytes_per_sec: d.write_rate,
+                read_iops: d.read_iops,
+                write_iops: d.write_iops,
+                await_ms: d.await_ms,
+                utilization_percent: d.utilization,
+            })
+            .collect(),
+        sensors: snapshot
+            .sensors
+            .iter()
+            .map(|s| Sensor {
+                label: s.label.clone(),
+                celsius: s.temperature,
+                max_celsius: s.max,
+                critical_celsius: s.critical,
+            })
+            .collect(),
+        processes: processes
+            .into_iter()
+            .take(top)
+            .map(|p| Process {
+                pid: p.pid,
+                parent: p.parent,
+                name: p.name.clone(),
+                user: p.user.clone(),
+                cpu_percent: p.cpu,
+                memory_bytes: p.memory,
+                read_bytes_per_sec: p.read_rate,
+                write_bytes_per_sec: p.write_rate,
+                state: p.state.clone(),
+                start_time: p.start_time,
+                command: p.command.clone(),
+            })
+            .collect(),
+    }
+}
@@ -0,0 +1,46 @@
+use std::{collections::HashMap, hash::Hash, time::Instant};
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Rate {
+    last: Option<(Instant, u64)>,
+}
+
+impl Rate {
+    pub fn update(&mut self, now: Instant, total: u64) -> f64 {
+        let rate = match self.last {
+            Some((then, prev)) => {
+                let elapsed = now.saturating_duration_since(then).as_secs_f64();
+                if elapsed > 0.0 {
+                    total.saturating_sub(prev) as f64 / elapsed
+                } else {
+                    0.0
+                }
+            }
+            None => 0.0,
+        };
+        self.last = Some((now, total));
+        rate
+    }
+}
+
+pub struct RateMap<K> {
+    rates: HashMap<K, (Rate, bool)>,
+}
+
+impl<K: Eq + Hash> RateMap<K> {
+    pub fn new() -> Self {
+        Self {
+            rates: HashMap::new(),
+        }
+    }
+
+    pub fn update(&mut self, key: K, now: Instant, total: u64) -> f64 {
+        let (rate, seen) = self.rates.entry(key).or_default();
+        *seen = true;
+        rate.update(now, total)
+    }
+
+    pub fn sweep(&mut self) {
+        self.rates.retain(|_, (_, seen)| std::mem::take(seen));
+    }
+}
@@ -0,0 +1,347 @@
+use crate::{
+    config::Threshold,
+    history::{self, History, Series, Window},
+};
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+};
+use sysinfo::Components;
+use tui::{
+    Frame,
+    backend::Backend,
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    widgets::{Block, Borders, Cell, Paragraph, Row, Table},
+};
+
+const CHART_COLORS: [Color; 6] = [
+    Color::Yellow,
+    Color::Cyan,
+    Color::Magenta,
+    Color::Green,
+    Color::Blue,
+    Color::Red,
+];
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct SensorSample {
+    pub label: String,
+    pub temperature: Option<f32>,
+    pub max: Option<f32>,
+    pub critical: Option<f32>,
+}
+
+impl SensorSample {
+    pub fn threshold(&self, configured: &Threshold) -> Threshold {
+        match self.critical {
+            Some(critical) if critical > 0.0 => {
+                let crit = critical as f64 - 5.0;
+                let warn = (critical as f64 * 0.8).min(crit);
+                Threshold::new(configured.warn.min(warn), configured.crit.min(crit))
+            }
+            _ => *configured,
+        }
+    }
+
+    pub fn color(&self, configured: &Threshold) -> Color {
+        let Some(temp) = self.temperature else {
+            return Color::DarkGray;
+        };
+        self.threshold(configured).color(temp as f64)
+    }
+}
+
+pub fn parse_fixture(text: &str) -> Vec<SensorSample> {
+    let value = |field: Option<&str>| field.and_then(|v| v.parse().ok());
+    text.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| {
+            let (label, values) = line.split_once('=')?;
+            let label = label.trim();
+            if label.is_empty() {
+                return None;
+            }
+            let mut values = values.split_whitespace();
+            Some(SensorSample {
+                label: label.to_string(),
+                temperature: value(values.next()),
+                max: value(values.next()),
+                critical: value(values.next()),
+            })
+        })
+        .collect()
+}
+
+pub enum SensorSource {
+    System(Components),
+    Fixture(PathBuf),
+}
+
+impl SensorSource {
+    pub fn from_env() -> Self {
+        match std::env::var_os("SYSTEMCLI_SENSORS") {
+            Some(path) => SensorSource::Fixture(PathBuf::from(path)),
+            None => SensorSource::System(Components::new_with_refreshed_list()),
+        }
+    }
+
+    pub fn sample(&mut self) -> Vec<SensorSample> {
+        let mut samples = match self {
+            SensorSource::System(components) => {
+                components.refresh(true);
+                components
+                    .iter()
+                    .map(|c| SensorSample {
+                        label: c.label().to_string(),
+                        temperature: c.temperature().filter(|t| t.is_finite()),
+                        max: c.max().filter(|t| t.is_finite()),
+                        critical: c.critical().filter(|t| t.is_finite()),
+                    })
+                    .collect()
+            }
+            SensorSource::Fixture(path) => read_fixture(path),
+        };
+        samples.sort_by(|a, b| a.label.cmp(&b.label));
+        samples
+    }
+}
+
+fn read_fixture(path: &Path) -> Vec<SensorSample> {
+    fs::read_to_string(path)
+        .map(|text| parse_fixture(&text))
+        .unwrap_or_default()
+}
+
+fn celsius(value: Option<f32>) -> String {
+    value
+        .map(|v| format!("{:.1}°C", v))
+        .unwrap_or_else(|| "-".to_string())
+}
+
+pub fn record(
+    histories: &mut HashMap<String, History>,
+    sensors: &[SensorSample],
+    now: std::time::Instant,
+) {
+    for sensor in sensors {
+        if let Some(temp) = sensor.temperature {
+            histories
+                .entry(sensor.label.clone())
+                .or_default()
+                .push(now, temp as f64);
+        }
+    }
+}
+
+pub fn draw<B: Backend>(
+    f: &mut Frame<B>,
+    area: Rect,
+    sensors: &[SensorSample],
+    histories: &HashMap<String, History>,
+    window: Window,
+    threshold: &Threshold,
+) {
+    if sensors.is_empty() {
+        let empty = Paragraph::new("No sensors available")
+            .style(Style::default().fg(Color::DarkGray))
+            .block(Block::default().title("Sensors").borders(Borders::ALL));
+        f.render_widget(empty, area);
+        return;
+    }
+
+    let columns = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(55), Constraint::Percentage(45)])
+        .split(area);
+
+    let header = Row::new(vec!["Sensor", "Temp", "Max", "Critical"]).style(
+        Style::default()
+            .fg(Color::Yellow)
+            .add_modifier(Modifier::BOLD),
+    );
+    let rows = sensors.iter().map(|s| {
+        Row::new(vec![
+            Cell::from(s.label.clone()),
+            Cell::from(celsius(s.temperature)).style(Style::default().fg(s.color(threshold))),
+            Cell::from(celsius(s.max)),
+            Cell::from(celsius(s.critical)),
+        ])
+    });
+    let widths = [
+        Constraint::Min(12),
+        Constraint::Length(8),
+        Constraint::Length(8),
+        Constraint::Length(9),
+    ];
+    let table = Table::new(rows)
+        .header(header)
+        .block(Block::default().title("Sensors").borders(Borders::ALL))
+        .widths(&widths);
+    f.render_widget(table, columns[0]);
+
+    let series: Vec<Series> = sensors
+        .iter()
+        .filter_map(|s| histories.get(&s.label).map(|h| (s, h)))
+        .take(CHART_COLORS.len())
+        .zip(CHART_COLORS)
+        .map(|((s, history), color)| Series {
+            name: &s.label,
+            history,
+            color,
+        })
+        .collect();
+    let max = sensors
+        .iter()
+        .filter_map(|s| s.critical.or(s.max).or(s.temperature))
+        .fold(50.0f32, f32::max) as f64;
+    history::draw(
+        f,
+        columns[1],
+        "Temperature",
+        &series,
+        max,
+        format!("{:.0}°C", max),
+        window,
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tui::{Terminal, backend::TestBackend};
+
+    fn fixture(name: &str) -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("fixtures/sensors")
+            .join(name)
+    }
+
+    fn sample(
+        label: &str,
+        temperature: Option<f32>,
+        max: Option<f32>,
+        critical: Option<f32>,
+    ) -> SensorSample {
+        SensorSample {
+            label: label.to_string(),
+            temperature,
+            max,
+            critical,
+        }
+    }
+
+    fn render(sensors: &[SensorSample]) -> String {
+        let mut terminal = Terminal::new(TestBackend::new(60, 8)).unwrap();
+        terminal
+            .draw(|f| {
+                let area = f.size();
+                draw(
+                    f,
+                    area,
+                    sensors,
+                    &HashMap::new(),
+                    Window::OneMinute,
+                    &Threshold::new(70.0, 85.0),
+                )
+            })
+            .unwrap();
+        terminal
+            .backend()
+            .buffer()
+            .content()
+            .iter()
+            .map(|cell| cell.symbol.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn parses_all_columns() {
+        let sensors = parse_fixture("cpu = 54.0 100.0 105.0\n");
+        assert_eq!(
+            sensors,
+            [sample("cpu", Some(54.0), Some(100.0), Some(105.0))]
+        );
+    }
+
+    #[test]
+    fn missing_and_invalid_columns_are_none() {
+        let sensors = SensorSource::Fixture(fixture("laptop.txt")).sample();
+        assert_eq!(
+            sensors,
+            [
+                sample("Core 0", Some(51.5), Some(100.0), None),
+                sample("Package id 0", Some(54.0), Some(100.0), Some(100.0)),
+                sample("acpitz", None, Some(95.0), Some(105.0)),
+                sample("gpu", Some(61.0), None, Some(95.0)),
+                sample("nvme Composite", Some(38.9), None, None),
+            ]
+        );
+    }
+
+    #[test]
+    fn skips_comments_and_blank_lines() {
+        assert!(parse_fixture("# cpu = 50.0\n\n   # gpu = 60.0\n").is_empty());
+        assert_eq!(
+            parse_fixture("# header\ncpu = 50.0 # trailing\n"),
+            [sample("cpu", Some(50.0), None, None)]
+        );
+    }
+
+    #[test]
+    fn empty_or_missing_fixture_has_no_sensors() {
+        for name in ["empty.txt", "comments-only.txt", "missing.txt"] {
+            assert!(
+                SensorSource::Fixture(fixture(name)).sample().is_empty(),
+                "{}",
+                name
+            );
+        }
+    }
+
+    #[test]
+    fn configured_threshold_applies_when_critical_is_higher() {
+        let configured = Threshold::new(60.0, 70.0);
+        let sensor = sample("cpu", Some(65.0), Some(100.0), Some(100.0));
+        assert_eq!(sensor.threshold(&configured), configured);
+        assert_eq!(sensor.color(&configured), Color::Yellow);
+        let sensor = sample("cpu", Some(72.0), Some(100.0), Some(100.0));
+        assert_eq!(sensor.color(&configured), Color::Red);
+    }
+
+    #[test]
+    fn low_critical_tightens_configured_threshold() {
+        let configured = Threshold::new(70.0, 85.0);
+        let sensor = sample("nvme", Some(70.0), None, Some(75.0));
+        assert_eq!(sensor.threshold(&configured), Threshold::new(60.0, 70.0));
+        assert_eq!(sensor.color(&configured), Color::Red);
+    }
+
+    #[test]
+    fn derived_bands_are_never_inverted() {
+        let configured = Threshold::new(70.0, 85.0);
+        for critical in [1.0, 10.0, 20.0, 24.0, 25.0, 30.0, 100.0] {
+            let threshold = sample("s", Some(0.0), None, Some(critical)).threshold(&configured);
+            assert!(threshold.warn <= threshold.crit, "critical {}", critical);
+        }
+        assert_eq!(
+            sample("s", Some(0.0), None, Some(20.0)).threshold(&configured),
+            Threshold::new(15.0, 15.0)
+        );
+    }
+
+    #[test]
+    fn missing_temperature_is_gray() {
+        let sensor = sample("cpu", None, None, Some(100.0));
+        assert_eq!(sensor.color(&Threshold::new(70.0, 85.0)), Color::DarkGray);
+    }
+
+    #[test]
+    fn draws_placeholder_without_sensors() {
+        let sensors = SensorSource::Fixture(fixture("empty.txt")).sample();
+        assert!(render(&sensors).contains("No sensors available"));
+        assert!(!render(&[sample("cpu", Some(50.0), None, None)]).contains("No sensors available"));
+    }
+}
@@ -0,0 +1,289 @@
+use crate::{
+    cli::ServeArgs,
+    collector::{self, Snapshot},
+    config::Config,
+    planner::Source,
+    prometheus,
+};
+use std::{error::Error, io, sync::Arc, time::Duration};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::{TcpListener, TcpStream},
+    sync::watch,
+};
+
+const MAX_REQUEST: usize = 8192;
+const READ_TIMEOUT: Duration = Duration::from_secs(10);
+const INDEX: &str = "<html><head><title>systemcli</title></head>\
+<body><h1>systemcli</h1><p><a href=\"/metrics\">Metrics</a></p></body></html>\n";
+
+struct Response {
+    status: &'static str,
+    content_type: &'static str,
+    body: String,
+}
+
+impl Response {
+    fn text(status: &'static str, body: &str) -> Self {
+        Response {
+            status,
+            content_type: "text/plain; charset=utf-8",
+            body: format!("{}\n", body),
+        }
+    }
+}
+
+async fn read_head(stream: &mut TcpStream) -> io::Result<Option<String>> {
+    let mut buf = Vec::new();
+    let mut chunk = [0; 1024];
+    while !buf.windows(4).any(|w| w == b"\r\n\r\n") {
+        if buf.len() > MAX_REQUEST {
+            return Ok(None);
+        }
+        let n = stream.read(&mut chunk).await?;
+        if n == 0 {
+            return Ok(None);
+        }
+        buf.extend_from_slice(&chunk[..n]);
+    }
+    Ok(Some(String::from_utf8_lossy(&buf).into_owned()))
+}
+
+fn respond(head: &str, snapshot: &Snapshot, top: usize) -> Response {
+    let mut parts = head.lines().next().unwrap_or_default().split_whitespace();
+    let (method, target) = (
+        parts.next().unwrap_or_default(),
+        parts.next().unwrap_or_default(),
+    );
+    let path = target.split('?').next().unwrap_or_default();
+    if method != "GET" && method != "HEAD" {
+        return Response::text("405 Method Not Allowed", "Method not allowed");
+    }
+    match path {
+        "/metrics" => Response {
+            status: "200 OK",
+            content_type: prometheus::CONTENT_TYPE,
+            body: prometheus::render(snapshot, top),
+        },
+        "/" => Response {
+            status: "200 OK",
+            content_type: "text/html; charset=utf-8",
+            body: INDEX.to_string(),
+        },
+        _ => Response::text("404 Not Found", "Not found"),
+    }
+}
+
+async fn handle(
+    mut stream: TcpStream,
+    snapshots: watch::Receiver<Arc<Snapshot>>,
+    top: usize,
+) -> io::Result<()> {
+    let head = match tokio::time::timeout(READ_TIMEOUT, read_head(&mut stream)).await {
+        Ok(head) => head?,
+        Err(_) => return Ok(()),
+    };
+    let (response, head_only) = match head {
+        Some(head) => {
+            let snapshot = snapshots.borrow().clone();
+            (respond(&head, &snapshot, top), head.starts_with("HEAD "))
+        }
+        None => (Response::text("400 Bad Request", "Bad request"), false),
+    };
+    let mut out = format!(
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        response.status,
+        response.content_type,
+        response.body.len()
+    );
+    if !head_only {
+        out.push_str(&response.body);
+    }
+    stream.write_all(out.as_bytes()).await?;
+    stream.shutdown().await
+}
+
+async fn serve(
+    listener: TcpListener,
+    mut snapshots: watch::Receiver<Arc<Snapshot>>,
+    top: usize,
+) -> io::Result<()> {
+    for _ in 0..2 {
+        if snapshots.changed().await.is_err() {
+            return Ok(());
+        }
+    }
+    loop {
+        let (stream, _) = listener.accept().await?;
+        let snapshots = snapshots.clone();
+        tokio::spawn(async move {
+            let _ = handle(stream, snapshots, top).await;
+        });
+    }
+}
+
+pub async fn run(config: Config, args: ServeArgs) -> Result<(), Box<dyn Error>> {
+    let listener = TcpListener::bind(args.prometheus)
+        .await
+        .map_err(|err| format!("cannot listen on {}: {}", args.prometheus, err))?;
+    let (_refresh, refresh_rx) = watch::channel(config.refresh.clone());
+    let (_demand, demand_rx) = watch::channel(Source::ALL.into_iter().collect());
+    let snapshots = collector::spawn(refresh_rx, demand_rx);
+    eprintln!(
+        "systemcli: serving Prometheus metrics on http://{}/metrics",
+        listener.local_addr()?
+    );
+    Ok(serve(listener, snapshots, args.top).await?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{memory::MemorySample, process::ProcessInfo, sensors::SensorSample};
+    use std::net::SocketAddr;
+
+    fn sensor(label: &str, temperature: Option<f32>) -> SensorSample {
+        SensorSample {
+            label: label.to_string(),
+            temperature,
+            max: None,
+            critical: None,
+        }
+    }
+
+    fn snapshot() -> Snapshot {
+        let mut snapshot = Snapshot {
+            memory: MemorySample {
+                total: 8 << 30,
+                ..MemorySample::default()
+            },
+            sensors: vec![
+                sensor("die \"0\"\\a\nb", Some(42.5)),
+                sensor("nvme", Some(30.0)),
+                sensor("nvme", None),
+                sensor("nvme", Some(31.0)),
+            ],
+            processes: vec![ProcessInfo {
+                pid: 42,
+                parent: None,
+                name: "worker".to_string(),
+                user: "root".to_string(),
+                cpu: 50.0,
+                memory: 1024,
+                read_rate: 0.0,
+                write_rate: 0.0,
+                state: "R".to_string(),
+                start_time: 0,
+                command: "worker".to_string(),
+            }],
+            ..Snapshot::default()
+        };
+        snapshot.host.boot_time = 1_700_000_000;
+        snapshot
+    }
+
+    async fn start(top: usize) -> (SocketAddr, watch::Sender<Arc<Snapshot>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let (tx, rx) = watch::channel(Arc::new(Snapshot::default()));
+        tokio::spawn(serve(listener, rx, top));
+        (addr, tx)
+    }
+
+    fn first() -> Snapshot {
+        Snapshot {
+            memory: MemorySample {
+                total: 1,
+                ..MemorySample::default()
+            },
+            ..Snapshot::default()
+        }
+    }
+
+    async fn publish(tx: &watch::Sender<Arc<Snapshot>>) {
+        tx.send(Arc::new(first())).unwrap();
+        tokio::task::yield_now().await;
+        tx.send(Arc::new(snapshot())).unwrap();
+    }
+
+    async fn request(addr: SocketAddr, method: &str, path: &str) -> (String, String) {
+        let mut stream = TcpStream::connect(addr).await.unwrap();
+        let request = format!("{} {} HTTP/1.1\r\nHost: localhost\r\n\r\n", method, path);
+        stream.write_all(request.as_bytes()).await.unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+        let (head, body) = response.split_once("\r\n\r\n").unwrap();
+        (head.to_string(), body.to_string())
+    }
+
+    #[tokio::test]
+    async fn serves_metrics() {
+        let (addr, tx) = start(1).await;
+        publish(&tx).await;
+        let (head, body) = request(addr, "GET", "/metrics").await;
+        assert!(head.starts_with("HTTP/1.1 200 OK\r\n"));
+        assert!(head.contains(&format!("Content-Type: {}", prometheus::CONTENT_TYPE)));
+        assert!(head.contains(&format!("Content-Length: {}", body.len())));
+        assert!(body.contains("# HELP systemcli_memory_total_bytes Total physical memory.\n"));
+        assert!(body.contains("# TYPE systemcli_memory_total_bytes gauge\n"));
+        assert!(body.contains("\nsystemcli_memory_total_bytes 8589934592\n"));
+        assert!(body.contains("# TYPE systemcli_network_receive_bytes_total counter\n"));
+        assert!(body.contains("\nsystemcli_boot_time_seconds 1700000000\n"));
+        assert!(body.contains(
+            "\nsystemcli_temperature_celsius{sensor=\"die \\\"0\\\"\\\\a\\nb\",index=\"0\"} 42.5\n"
+        ));
+        assert!(body.contains("\nsystemcli_temperature_celsius{sensor=\"nvme\",index=\"0\"} 30\n"));
+        assert!(body.contains("\nsystemcli_temperature_celsius{sensor=\"nvme\",index=\"2\"} 31\n"));
+        assert!(!body.contains("index=\"1\""));
+        assert!(body.contains(
+            "\nsystemcli_process_cpu_usage_ratio{pid=\"42\",name=\"worker\",user=\"root\"} 0.5\n"
+        ));
+    }
+
+    #[tokio::test]
+    async fn omits_processes_without_top() {
+        let (addr, tx) = start(0).await;
+        publish(&tx).await;
+        let (_, body) = request(addr, "GET", "/metrics?x=1").await;
+        assert!(body.contains("systemcli_cpu_usage_ratio"));
+        assert!(!body.contains("systemcli_process_"));
+    }
+
+    #[tokio::test]
+    async fn head_has_no_body() {
+        let (addr, tx) = start(0).await;
+        publish(&tx).await;
+        let (head, body) = request(addr, "HEAD", "/metrics").await;
+        assert!(head.starts_with("HTTP/1.1 200 OK\r\n"));
+        assert!(!head.contains("Content-Length: 0\r\n"));
+        assert!(body.is_empty());
+    }
+
+    #[tokio::test]
+    async fn rejects_unknown_paths_and_methods() {
+        let (addr, tx) = start(0).await;
+        publish(&tx).await;
+        let (head, _) = request(addr, "GET", "/nope").await;
+        assert!(head.starts_with("HTTP/1.1 404 Not Found\r\n"));
+        let (head, _) = request(addr, "POST", "/metrics").await;
+        assert!(head.starts_with("HTTP/1.1 405 Method Not Allowed\r\n"));
+        let (head, body) = request(addr, "GET", "/").await;
+        assert!(head.starts_with("HTTP/1.1 200 OK\r\n"));
+        assert!(body.contains("/metrics"));
+    }
+
+    #[tokio::test]
+    async fn skips_first_snapshot() {
+        let (addr, tx) = start(0).await;
+        let pending = tokio::spawn(request(addr, "GET", "/metrics"));
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert!(!pending.is_finished());
+        tx.send(Arc::new(first())).unwrap();
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert!(!pending.is_finished());
+        publish(&tx).await;
+        let (head, body) = pending.await.unwrap();
+        assert!(head.starts_with("HTTP/1.1 200 OK\r\n"));
+        assert!(body.contains("\nsystemcli_memory_total_bytes 8589934592\n"));
+    }
+}
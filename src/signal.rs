@@ -0,0 +1,205 @@
+use crate::ui;
+use std::io;
+use tui::{
+    Frame,
+    backend::Backend,
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Span, Spans},
+    widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph},
+};
+
+pub const MENU: [i32; 5] = [
+    libc::SIGTERM,
+    libc::SIGKILL,
+    libc::SIGSTOP,
+    libc::SIGCONT,
+    libc::SIGHUP,
+];
+
+const NAMES: [(i32, &str); 19] = [
+    (libc::SIGHUP, "SIGHUP"),
+    (libc::SIGINT, "SIGINT"),
+    (libc::SIGQUIT, "SIGQUIT"),
+    (libc::SIGILL, "SIGILL"),
+    (libc::SIGTRAP, "SIGTRAP"),
+    (libc::SIGABRT, "SIGABRT"),
+    (libc::SIGBUS, "SIGBUS"),
+    (libc::SIGFPE, "SIGFPE"),
+    (libc::SIGKILL, "SIGKILL"),
+    (libc::SIGUSR1, "SIGUSR1"),
+    (libc::SIGSEGV, "SIGSEGV"),
+    (libc::SIGUSR2, "SIGUSR2"),
+    (libc::SIGPIPE, "SIGPIPE"),
+    (libc::SIGALRM, "SIGALRM"),
+    (libc::SIGTERM, "SIGTERM"),
+    (libc::SIGCHLD, "SIGCHLD"),
+    (libc::SIGCONT, "SIGCONT"),
+    (libc::SIGSTOP, "SIGSTOP"),
+    (libc::SIGTSTP, "SIGTSTP"),
+];
+
+pub fn name(signal: i32) -> String {
+    NAMES
+        .iter()
+        .find(|(number, _)| *number == signal)
+        .map(|(_, name)| name.to_string())
+        .unwrap_or_else(|| format!("signal {}", signal))
+}
+
+fn describe(err: &io::Error) -> String {
+    match err.raw_os_error() {
+        Some(libc::EPERM) => "EPERM (operation not permitted)".to_string(),
+        Some(libc::ESRCH) => "ESRCH (no such process)".to_string(),
+        Some(libc::EINVAL) => "EINVAL (invalid signal)".to_string(),
+        _ => err.to_string(),
+    }
+}
+
+pub fn send(pid: u32, signal: i32) -> io::Result<()> {
+    let pid = match i32::try_from(pid) {
+        Ok(pid) if pid > 0 => pid,
+        _ => return Err(io::Error::from_raw_os_error(libc::ESRCH)),
+    };
+    if unsafe { libc::kill(pid, signal) } == 0 {
+        Ok(())
+    } else {
+        Err(io::Error::last_os_error())
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Target {
+    pub pids: Vec<u32>,
+    pub label: String,
+}
+
+pub struct SignalMenu {
+    pub target: Target,
+    pub selected: usize,
+    pub custom: String,
+}
+
+impl SignalMenu {
+    pub fn new(target: Target) -> Self {
+        Self {
+            target,
+            selected: 0,
+            custom: String::new(),
+        }
+    }
+
+    pub fn move_selection(&mut self, delta: isize) {
+        self.custom.clear();
+        self.selected = self
+            .selected
+            .saturating_add_signed(delta)
+            .min(MENU.len() - 1);
+    }
+
+    pub fn push_digit(&mut self, digit: char) {
+        if self.custom.len() < 2 {
+            self.custom.push(digit);
+        }
+    }
+
+    pub fn signal(&self) -> Option<i32> {
+        if self.custom.is_empty() {
+            Some(MENU[self.selected])
+        } else {
+            self.custom.parse().ok().filter(|n| (1..=64).contains(n))
+        }
+    }
+
+    pub fn draw<B: Backend>(&self, f: &mut Frame<B>, area: Rect) {
+        let area = ui::centered_rect(40, MENU.len() as u16 + 5, area);
+        let items: Vec<ListItem> = MENU
+            .iter()
+            .map(|&signal| ListItem::new(format!("{:>2} {}", signal, name(signal))))
+            .collect();
+        let mut state = ListState::default();
+        if self.custom.is_empty() {
+            state.select(Some(self.selected));
+        }
+        let block = Block::default()
+            .title(format!("Signal {}", self.target.label))
+            .borders(Borders::ALL);
+        let inner = block.inner(area);
+        f.render_widget(Clear, area);
+        f.render_widget(block, area);
+
+        let list =
+            List::new(items).highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+        let list_area = Rect {
+            height: inner.height.saturating_sub(2),
+            ..inner
+        };
+        f.render_stateful_widget(list, list_area, &mut state);
+
+        let prompt = Paragraph::new(vec![
+            Spans::from(vec![
+                Span::raw("Number: "),
+                Span::styled(self.custom.clone(), Style::default().fg(Color::Yellow)),
+            ]),
+            Spans::from("Enter select, Esc cancel"),
+        ]);
+        let prompt_area = Rect {
+            y: list_area.y + list_area.height,
+            height: 2,
+            ..inner
+        };
+        f.render_widget(prompt, prompt_area);
+    }
+}
+
+pub struct PendingSignal {
+    pub target: Target,
+    pub signal: i32,
+}
+
+impl PendingSignal {
+    pub fn send(&self) -> String {
+        let name = name(self.signal);
+        let failures: Vec<String> = self
+            .target
+            .pids
+            .iter()
+            .filter_map(|&pid| {
+                send(pid, self.signal)
+                    .err()
+                    .map(|err| format!("{}: {}", pid, describe(&err)))
+            })
+            .collect();
+        if failures.is_empty() {
+            format!("Sent {} to {}", name, self.target.label)
+        } else {
+            format!(
+                "{} to {} failed: {}",
+                name,
+                self.target.label,
+                failures.join(", ")
+            )
+        }
+    }
+
+    pub fn draw<B: Backend>(&self, f: &mut Frame<B>, area: Rect) {
+        let area = ui::centered_rect(50, 5, area);
+        let text = vec![
+            Spans::from(format!(
+                "Send {} to {}?",
+                name(self.signal),
+                self.target.label
+            )),
+            Spans::from(""),
+            Spans::from("y confirm, n cancel"),
+        ];
+        let dialog = Paragraph::new(text).block(
+            Block::default()
+                .title("Confirm")
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(Color::Red)),
+        );
+        f.render_widget(Clear, area);
+        f.render_widget(dialog, area);
+    }
+}
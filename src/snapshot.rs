@@ -0,0 +1,217 @@
+use crate::{
+    cli::{OutputFormat, SnapshotArgs},
+    collector::Collector,
+    config::Config,
+    format::{self, RateUnit},
+    memory,
+    planner::Source,
+    report::{self, Report},
+};
+use std::{
+    error::Error,
+    io::{self, Write},
+};
+
+pub async fn run(config: Config, args: SnapshotArgs, colors: bool) -> Result<(), Box<dyn Error>> {
+    let delay = args
+        .delay
+        .unwrap_or_else(|| config.refresh.interval(Source::Cpu));
+    let mut collector = Collector::new(&config.refresh);
+    collector.sample_all();
+    tokio::time::sleep(delay).await;
+    let snapshot = collector.sample_all();
+    let report = report::build(&snapshot, delay, args.top);
+
+    let mut out = io::stdout().lock();
+    match args.format {
+        OutputFormat::Json => {
+            serde_json::to_writer_pretty(&mut out, &report)?;
+            writeln!(out)?;
+        }
+        OutputFormat::Yaml => serde_yaml::to_writer(&mut out, &report)?,
+        OutputFormat::Table => write_table(&mut out, &report, config.units.rate, colors)?,
+    }
+    out.flush()?;
+    Ok(())
+}
+
+struct Table<'a> {
+    header: &'a [&'a str],
+    align: &'a str,
+    rows: Vec<Vec<String>>,
+}
+
+impl Table<'_> {
+    fn write(&self, out: &mut impl Write, colors: bool) -> io::Result<()> {
+        if self.rows.is_empty() {
+            return Ok(());
+        }
+        let widths: Vec<usize> = self
+            .header
+            .iter()
+            .enumerate()
+            .map(|(i, h)| {
+                self.rows
+                    .iter()
+                    .map(|row| row[i].chars().count())
+                    .chain([h.len()])
+                    .max()
+                    .unwrap_or(0)
+            })
+            .collect();
+        let line = |cells: Vec<&str>| {
+            cells
+                .iter()
+                .zip(&widths)
+                .zip(self.align.chars())
+                .map(|((cell, &width), align)| match align {
+                    '>' => format!("{:>width$}", cell),
+                    _ => format!("{:<width$}", cell),
+                })
+                .collect::<Vec<_>>()
+                .join("  ")
+                .trim_end()
+                .to_string()
+        };
+        let header = line(self.header.to_vec());
+        if colors {
+            writeln!(out, "\x1b[1m{}\x1b[0m", header)?;
+        } else {
+            writeln!(out, "{}", header)?;
+        }
+        for row in &self.rows {
+            writeln!(out, "{}", line(row.iter().map(String::as_str).collect()))?;
+        }
+        writeln!(out)
+    }
+}
+
+fn write_table(
+    out: &mut impl Write,
+    report: &Report,
+    unit: RateUnit,
+    colors: bool,
+) -> io::Result<()> {
+    let host = &report.host;
+    let memory = &report.memory;
+    writeln!(
+        out,
+        "{} {}  {}  up {}  load {:.2} {:.2} {:.2}",
+        host.hostname,
+        host.kernel,
+        format::datetime(report.timestamp),
+        format::duration(host.uptime_secs),
+        host.load[0],
+        host.load[1],
+        host.load[2]
+    )?;
+    writeln!(
+        out,
+        "CPU {:.1}%  Memory {}/{} {}  Swap {}/{} {}",
+        report.cpu.usage_percent,
+        format::bytes(memory.used_bytes),
+        format::bytes(memory.total_bytes),
+        memory::describe_percent(memory.used_percent),
+        format::bytes(memory.swap_used_bytes),
+        format::bytes(memory.swap_total_bytes),
+        memory::describe_percent(memory.swap_used_percent)
+    )?;
+    writeln!(out)?;
+
+    Table {
+        header: &["INTERFACE", "RX", "TX", "RX TOTAL", "TX TOTAL", "ERRORS"],
+        align: "<>>>>>",
+        rows: report
+            .network
+            .iter()
+            .map(|i| {
+                vec![
+                    i.name.clone(),
+                    format::rate(i.rx_bytes_per_sec, unit),
+                    format::rate(i.tx_bytes_per_sec, unit),
+                    format::bytes(i.rx_bytes_total),
+                    format::bytes(i.tx_bytes_total),
+                    (i.rx_errors + i.tx_errors).to_string(),
+                ]
+            })
+            .collect(),
+    }
+    .write(out, colors)?;
+
+    Table {
+        header: &["FILESYSTEM", "MOUNT", "TYPE", "USED", "SIZE", "USE%"],
+        align: "<<<>>>",
+        rows: report
+            .filesystems
+            .iter()
+            .map(|d| {
+                vec![
+                    d.device.clone(),
+                    d.mount_point.clone(),
+                    d.fs_type.clone(),
+                    format::bytes(d.used_bytes),
+                    format::bytes(d.total_bytes),
+                    format!("{:.1}", d.used_percent),
+                ]
+            })
+            .collect(),
+    }
+    .write(out, colors)?;
+
+    Table {
+        header: &["DEVICE", "READ", "WRITE", "IOPS", "AWAIT", "UTIL%"],
+        align: "<>>>>>",
+        rows: report
+            .disks
+            .iter()
+            .map(|d| {
+                vec![
+                    d.name.clone(),
+                    format::rate(d.read_bytes_per_sec, unit),
+                    format::rate(d.write_bytes_per_sec, unit),
+                    format!("{:.0}", d.read_iops + d.write_iops),
+                    format!("{:.1} ms", d.await_ms),
+                    format!("{:.1}", d.utilization_percent),
+                ]
+            })
+            .collect(),
+    }
+    .write(out, colors)?;
+
+    Table {
+        header: &["SENSOR", "TEMP", "CRIT"],
+        align: "<>>",
+        rows: report
+            .sensors
+            .iter()
+            .map(|s| {
+                let celsius = |t: Option<f32>| t.map(|t| format!("{:.1}°C", t)).unwrap_or_default();
+                vec![
+                    s.label.clone(),
+                    celsius(s.celsius),
+                    celsius(s.critical_celsius),
+                ]
+            })
+            .collect(),
+    }
+    .write(out, colors)?;
+
+    Table {
+        header: &["PID", "USER", "CPU%", "MEM", "NAME"],
+        align: "><>><",
+        rows: report
+            .processes
+            .iter()
+            .map(|p| {
+                vec![
+                    p.pid.to_string(),
+                    p.user.clone(),
+                    format!("{:.1}", p.cpu_percent),
+                    format::bytes(p.memory_bytes),
+                    p.name.clone(),
+                ]
+            })
+            .collect(),
+    }
+    .write(out, colors)
+}
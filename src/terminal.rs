@@ -0,0 +1,138 @@
+use crossterm::{
+    cursor::Show,
+    event::{DisableMouseCapture, EnableMouseCapture},
+    execute,
+    terminal::{EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode},
+};
+use std::{
+    io::{self, Stdout},
+    sync::atomic::{AtomicBool, Ordering},
+};
+use tokio::signal::unix::{SignalKind, signal};
+use tui::{
+    Terminal,
+    backend::{Backend, CrosstermBackend},
+    buffer::Cell,
+    layout::Rect,
+    style::Color,
+};
+
+static ACTIVE: AtomicBool = AtomicBool::new(false);
+
+pub fn restore() -> io::Result<()> {
+    if !ACTIVE.swap(false, Ordering::SeqCst) {
+        return Ok(());
+    }
+    let raw = disable_raw_mode();
+    execute!(
+        io::stdout(),
+        LeaveAlternateScreen,
+        DisableMouseCapture,
+        Show
+    )?;
+    raw
+}
+
+pub struct ColorFilter<B> {
+    inner: B,
+    colors: bool,
+}
+
+impl<B: Backend> Backend for ColorFilter<B> {
+    fn draw<'a, I>(&mut self, content: I) -> io::Result<()>
+    where
+        I: Iterator<Item = (u16, u16, &'a Cell)>,
+    {
+        if self.colors {
+            return self.inner.draw(content);
+        }
+        let cells: Vec<(u16, u16, Cell)> = content
+            .map(|(x, y, cell)| {
+                let mut cell = cell.clone();
+                cell.fg = Color::Reset;
+                cell.bg = Color::Reset;
+                (x, y, cell)
+            })
+            .collect();
+        self.inner
+            .draw(cells.iter().map(|(x, y, cell)| (*x, *y, cell)))
+    }
+
+    fn hide_cursor(&mut self) -> io::Result<()> {
+        self.inner.hide_cursor()
+    }
+
+    fn show_cursor(&mut self) -> io::Result<()> {
+        self.inner.show_cursor()
+    }
+
+    fn get_cursor(&mut self) -> io::Result<(u16, u16)> {
+        self.inner.get_cursor()
+    }
+
+    fn set_cursor(&mut self, x: u16, y: u16) -> io::Result<()> {
+        self.inner.set_cursor(x, y)
+    }
+
+    fn clear(&mut self) -> io::Result<()> {
+        self.inner.clear()
+    }
+
+    fn size(&self) -> io::Result<Rect> {
+        self.inner.size()
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+pub struct TerminalGuard {
+    pub terminal: Terminal<ColorFilter<CrosstermBackend<Stdout>>>,
+}
+
+impl TerminalGuard {
+    pub fn enter(colors: bool) -> io::Result<Self> {
+        install_panic_hook();
+        ACTIVE.store(true, Ordering::SeqCst);
+        enable_raw_mode()?;
+        let mut stdout = io::stdout();
+        execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
+        let backend = ColorFilter {
+            inner: CrosstermBackend::new(stdout),
+            colors,
+        };
+        let terminal = Terminal::new(backend)?;
+        Ok(Self { terminal })
+    }
+}
+
+impl Drop for TerminalGuard {
+    fn drop(&mut self) {
+        let _ = restore();
+    }
+}
+
+fn install_panic_hook() {
+    let default = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        let _ = restore();
+        default(info);
+    }));
+}
+
+pub fn spawn_signal_handler() -> io::Result<()> {
+    let mut terminate = signal(SignalKind::terminate())?;
+    let mut interrupt = signal(SignalKind::interrupt())?;
+    let mut hangup = signal(SignalKind::hangup())?;
+    tokio::spawn(async move {
+        let code = tokio::select! {
+            _ = terminate.recv() => 128 + libc::SIGTERM,
+            _ = interrupt.recv() => 128 + libc::SIGINT,
+            _ = hangup.recv() => 128 + libc::SIGHUP,
+        };
+        let _ = restore();
+        std::process::exit(code);
+    });
+    Ok(())
+}
@@ -0,0 +1,72 @@
+use crate::{
+    app::App,
+    cli::Overrides,
+    collector,
+    config::{self, Config},
+    input, reload,
+    terminal::{self, TerminalGuard},
+    ui,
+};
+use crossterm::event::Event;
+use std::{error::Error, path::Path};
+use tokio::sync::{mpsc, watch};
+
+pub async fn run(
+    config: Config,
+    config_path: Option<&Path>,
+    overrides: Overrides,
+    colors: bool,
+) -> Result<(), Box<dyn Error>> {
+    let mut guard = TerminalGuard::enter(colors)?;
+    terminal::spawn_signal_handler()?;
+
+    let (refresh, refresh_rx) = watch::channel(config.refresh.clone());
+    let mut app = App::new(config);
+    let (demand, demand_rx) = watch::channel(app.visible_sources());
+    let mut snapshots = collector::spawn(refresh_rx, demand_rx);
+    let mut events = input::spawn();
+    let mut reloads = match config::resolve(config_path) {
+        Some(path) => reload::spawn(path),
+        None => mpsc::unbounded_channel().1,
+    };
+
+    loop {
+        guard.terminal.draw(|f| ui::draw(f, &mut app))?;
+        let visible = app.visible_sources();
+        demand.send_if_modified(|current| {
+            let changed = *current != visible;
+            *current = visible;
+            changed
+        });
+
+        tokio::select! {
+            changed = snapshots.changed() => {
+                if changed.is_err() {
+                    break;
+                }
+                let snapshot = snapshots.borrow_and_update().clone();
+                app.apply(snapshot);
+            }
+            event = events.recv() => match event {
+                Some(Event::Key(key)) => {
+                    if app.on_key(key) {
+                        break;
+                    }
+                }
+                Some(_) => {}
+                None => break,
+            },
+            Some(result) = reloads.recv() => {
+                app.reload(result.map(|config| overrides.apply(config)));
+                refresh.send_if_modified(|current| {
+                    let changed = *current != app.config.refresh;
+                    current.clone_from(&app.config.refresh);
+                    changed
+                });
+            }
+        }
+    }
+
+    drop(guard);
+    Ok(())
+}
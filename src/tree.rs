@@ -0,0 +1,130 @@
+use crate::process::ProcessInfo;
+use std::collections::{HashMap, HashSet};
+
+#[derive(Clone, Debug)]
+pub struct ViewRow {
+    pub index: usize,
+    pub prefix: String,
+    pub children: usize,
+    pub collapsed: bool,
+    pub total_cpu: f32,
+    pub total_memory: u64,
+}
+
+pub fn flat(processes: &[ProcessInfo]) -> Vec<ViewRow> {
+    processes
+        .iter()
+        .enumerate()
+        .map(|(index, p)| ViewRow {
+            index,
+            prefix: String::new(),
+            children: 0,
+            collapsed: false,
+            total_cpu: p.cpu,
+            total_memory: p.memory,
+        })
+        .collect()
+}
+
+fn children(processes: &[ProcessInfo]) -> (Vec<usize>, HashMap<u32, Vec<usize>>) {
+    let pids: HashSet<u32> = processes.iter().map(|p| p.pid).collect();
+    let mut roots = Vec::new();
+    let mut children: HashMap<u32, Vec<usize>> = HashMap::new();
+    for (index, p) in processes.iter().enumerate() {
+        match p
+            .parent
+            .filter(|parent| *parent != p.pid && pids.contains(parent))
+        {
+            Some(parent) => children.entry(parent).or_default().push(index),
+            None => roots.push(index),
+        }
+    }
+    (roots, children)
+}
+
+struct Builder<'a> {
+    processes: &'a [ProcessInfo],
+    children: HashMap<u32, Vec<usize>>,
+    collapsed: &'a HashSet<u32>,
+    visited: HashSet<u32>,
+    rows: Vec<ViewRow>,
+}
+
+impl Builder<'_> {
+    fn visit(&mut self, index: usize, indent: &str, branch: &str, hidden: bool) -> (f32, u64) {
+        let process = &self.processes[index];
+        if !self.visited.insert(process.pid) {
+            return (0.0, 0);
+        }
+        let kids = self.children.get(&process.pid).cloned().unwrap_or_default();
+        let collapsed = self.collapsed.contains(&process.pid);
+
+        let row = self.rows.len();
+        if !hidden {
+            self.rows.push(ViewRow {
+                index,
+                prefix: format!("{}{}", indent, branch),
+                children: kids.len(),
+                collapsed,
+                total_cpu: 0.0,
+                total_memory: 0,
+            });
+        }
+
+        let child_indent = match branch {
+            "├─ " => format!("{}│  ", indent),
+            "└─ " => format!("{}   ", indent),
+            _ => indent.to_string(),
+        };
+        let (mut cpu, mut memory) = (process.cpu, process.memory);
+        for (i, &child) in kids.iter().enumerate() {
+            let branch = if i + 1 == kids.len() {
+                "└─ "
+            } else {
+                "├─ "
+            };
+            let (c, m) = self.visit(child, &child_indent, branch, hidden || collapsed);
+            cpu += c;
+            memory += m;
+        }
+
+        if !hidden {
+            self.rows[row].total_cpu = cpu;
+            self.rows[row].total_memory = memory;
+        }
+        (cpu, memory)
+    }
+}
+
+pub fn build(processes: &[ProcessInfo], collapsed: &HashSet<u32>) -> Vec<ViewRow> {
+    let (roots, children) = children(processes);
+    let mut builder = Builder {
+        processes,
+        children,
+        collapsed,
+        visited: HashSet::new(),
+        rows: Vec::with_capacity(processes.len()),
+    };
+    for root in roots {
+        builder.visit(root, "", "", false);
+    }
+    builder.rows
+}
+
+pub fn subtree(processes: &[ProcessInfo], pid: u32) -> Vec<u32> {
+    let (_, children) = children(processes);
+    let mut pids = vec![pid];
+    let mut seen: HashSet<u32> = HashSet::from([pid]);
+    let mut i = 0;
+    while i < pids.len() {
+        for &child in children.get(&pids[i]).into_iter().flatten() {
+            let child = processes[child].pid;
+            if seen.insert(child) {
+                pids.push(child);
+            }
+        }
+        i += 1;
+    }
+    pids.reverse();
+    pids
+}
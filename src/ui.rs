@@ -0,0 +1,313 @@
+use crate::{
+    app::{App, Mode},
+    cpu, disk, diskstats, format,
+    history::{self, Series},
+    host,
+    layout::Panel,
+    memory,
+    network::{self, Scale},
+    sensors,
+};
+use tui::{
+    Frame,
+    backend::Backend,
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::Spans,
+    widgets::{Block, Borders, Gauge, Paragraph, Tabs},
+};
+
+pub fn centered_rect(width: u16, height: u16, area: Rect) -> Rect {
+    let width = width.min(area.width);
+    let height = height.min(area.height);
+    Rect {
+        x: area.x + (area.width - width) / 2,
+        y: area.y + (area.height - height) / 2,
+        width,
+        height,
+    }
+}
+
+fn natural_height(app: &App, panel: Panel) -> u16 {
+    let snapshot = &app.snapshot;
+    match panel {
+        Panel::Header | Panel::Status => 1,
+        Panel::Gauges => 15,
+        Panel::Cores => snapshot.cores.len().div_ceil(2).clamp(2, 8) as u16 + 2,
+        Panel::History => 16,
+        Panel::Network => snapshot.interfaces.len().clamp(3, 6) as u16 + 3,
+        Panel::Sensors => snapshot.sensors.len().clamp(3, 6) as u16 + 3,
+        Panel::Filesystems => {
+            snapshot
+                .disks
+                .iter()
+                .filter(|d| app.disk_filter.matches(d))
+                .count()
+                .clamp(4, 6) as u16
+                + 3
+        }
+        Panel::BlockIo => snapshot.disk_io.len().clamp(4, 6) as u16 + 3,
+        Panel::Memory => 7,
+        Panel::Processes => 10,
+    }
+}
+
+pub fn draw<B: Backend>(f: &mut Frame<B>, app: &mut App) {
+    let size = f.size();
+    let rows = Layout::default()
+        .margin(app.config.layout.margin)
+        .constraints([Constraint::Length(1), Constraint::Min(0)])
+        .split(size);
+    draw_tabs(f, rows[0], app);
+    let placed = app
+        .layout
+        .place(rows[1], &|panel| natural_height(app, panel));
+    for (panel, area) in placed {
+        match panel {
+            Panel::Processes => app.processes.draw(f, area),
+            panel => draw_panel(f, area, panel, app),
+        }
+    }
+
+    match &app.mode {
+        Mode::Normal => {}
+        Mode::SignalMenu(menu) => menu.draw(f, size),
+        Mode::Confirm(pending) => pending.draw(f, size),
+    }
+}
+
+fn draw_panel<B: Backend>(f: &mut Frame<B>, area: Rect, panel: Panel, app: &App) {
+    let snapshot = &app.snapshot;
+    let thresholds = &app.config.thresholds;
+    match panel {
+        Panel::Header => host::draw(f, area, &snapshot.host, app.config.colors.header.0),
+        Panel::Gauges => draw_gauges(f, area, app),
+        Panel::Cores => cpu::draw(f, area, &snapshot.cores, &thresholds.cpu),
+        Panel::History => draw_history(f, area, app),
+        Panel::Network => network::draw_table(
+            f,
+            area,
+            &snapshot.interfaces,
+            &app.net_filter,
+            app.rate_unit,
+        ),
+        Panel::Sensors => sensors::draw(
+            f,
+            area,
+            &snapshot.sensors,
+            &app.sensor_history,
+            app.window,
+            &thresholds.temperature,
+        ),
+        Panel::Filesystems => {
+            disk::draw(f, area, &snapshot.disks, &app.disk_filter, &thresholds.disk)
+        }
+        Panel::BlockIo => diskstats::draw(
+            f,
+            area,
+            &snapshot.disk_io,
+            app.rate_unit,
+            &thresholds.disk_io,
+        ),
+        Panel::Memory => memory::draw(f, area, &snapshot.memory),
+        Panel::Processes => {}
+        Panel::Status => draw_status(f, area, app),
+    }
+}
+
+fn draw_tabs<B: Backend>(f: &mut Frame<B>, area: Rect, app: &App) {
+    let titles = app
+        .tabs
+        .iter()
+        .enumerate()
+        .map(|(i, tab)| Spans::from(format!("{} {}", i + 1, tab.title)))
+        .collect();
+    let tabs = Tabs::new(titles).select(app.tab).highlight_style(
+        Style::default()
+            .fg(app.config.colors.header.0)
+            .add_modifier(Modifier::BOLD | Modifier::REVERSED),
+    );
+    f.render_widget(tabs, area);
+}
+
+fn draw_gauges<B: Backend>(f: &mut Frame<B>, area: Rect, app: &App) {
+    let colors = &app.config.colors;
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Percentage(20),
+            Constraint::Percentage(20),
+            Constraint::Percentage(20),
+            Constraint::Percentage(20),
+            Constraint::Percentage(20),
+        ])
+        .split(area);
+
+    let cpu_gauge = Gauge::default()
+        .block(Block::default().title("CPU Usage").borders(Borders::ALL))
+        .gauge_style(Style::default().fg(colors.cpu.0))
+        .percent(app.snapshot.cpu_usage as u16);
+    f.render_widget(cpu_gauge, chunks[0]);
+
+    let memory = &app.snapshot.memory;
+    let mem_gauge = Gauge::default()
+        .block(Block::default().title("Memory Usage").borders(Borders::ALL))
+        .gauge_style(Style::default().fg(colors.memory.0))
+        .ratio(memory.used_percent().unwrap_or(0.0) / 100.0)
+        .label(memory::describe_percent(memory.used_percent()));
+    f.render_widget(mem_gauge, chunks[1]);
+
+    let swap_gauge = Gauge::default()
+        .block(Block::default().title("Swap Usage").borders(Borders::ALL))
+        .gauge_style(Style::default().fg(colors.swap.0))
+        .ratio(memory.swap_percent().unwrap_or(0.0) / 100.0)
+        .label(memory::describe_percent(memory.swap_percent()));
+    f.render_widget(swap_gauge, chunks[2]);
+
+    let throughput = app.throughput;
+    network::draw_gauge(
+        f,
+        chunks[3],
+        "Download",
+        throughput.rx,
+        Scale::new(throughput.link_speed, &app.download_peak),
+        app.rate_unit,
+        colors.download.0,
+    );
+    network::draw_gauge(
+        f,
+        chunks[4],
+        "Upload",
+        throughput.tx,
+        Scale::new(throughput.link_speed, &app.upload_peak),
+        app.rate_unit,
+        colors.upload.0,
+    );
+}
+
+fn draw_status<B: Backend>(f: &mut Frame<B>, area: Rect, app: &App) {
+    let overhead = &app.snapshot.overhead;
+    let overhead = format!(
+        "SystemCLI {:.1}% CPU {} sample {} ms",
+        overhead.cpu,
+        format::bytes(overhead.memory),
+        overhead.sample_time.as_millis()
+    );
+    let status = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([
+            Constraint::Min(0),
+            Constraint::Length(overhead.chars().count() as u16),
+        ])
+        .split(area);
+    if let Some(message) = &app.status {
+        f.render_widget(
+            Paragraph::new(message.as_str()).style(Style::default().fg(Color::Yellow)),
+            status[0],
+        );
+    }
+    f.render_widget(
+        Paragraph::new(overhead).style(Style::default().fg(Color::DarkGray)),
+        status[1],
+    );
+}
+
+fn draw_history<B: Backend>(f: &mut Frame<B>, area: Rect, app: &App) {
+    let colors = &app.config.colors;
+    let charts = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Ratio(1, 4),
+            Constraint::Ratio(1, 4),
+            Constraint::Ratio(1, 4),
+            Constraint::Ratio(1, 4),
+        ])
+        .split(area);
+
+    history::draw(
+        f,
+        charts[0],
+        "CPU %",
+        &[Series {
+            name: "cpu",
+            history: &app.cpu_history,
+            color: colors.cpu.0,
+        }],
+        100.0,
+        "100".to_string(),
+        app.window,
+    );
+    history::draw(
+        f,
+        charts[1],
+        "Memory/Swap %",
+        &[
+            Series {
+                name: "mem",
+                history: &app.memory_history,
+                color: colors.memory.0,
+            },
+            Series {
+                name: "swap",
+                history: &app.swap_history,
+                color: colors.swap.0,
+            },
+        ],
+        100.0,
+        "100".to_string(),
+        app.window,
+    );
+
+    let now = std::time::Instant::now();
+    let peak = app
+        .rx_history
+        .max(now, app.window)
+        .max(app.tx_history.max(now, app.window))
+        .max(1024.0);
+    history::draw(
+        f,
+        charts[2],
+        "Network",
+        &[
+            Series {
+                name: "rx",
+                history: &app.rx_history,
+                color: colors.download.0,
+            },
+            Series {
+                name: "tx",
+                history: &app.tx_history,
+                color: colors.upload.0,
+            },
+        ],
+        peak,
+        format::rate(peak, app.rate_unit),
+        app.window,
+    );
+
+    let peak = app
+        .disk_read_history
+        .max(now, app.window)
+        .max(app.disk_write_history.max(now, app.window))
+        .max(1024.0);
+    history::draw(
+        f,
+        charts[3],
+        "Disk I/O",
+        &[
+            Series {
+                name: "read",
+                history: &app.disk_read_history,
+                color: colors.disk_read.0,
+            },
+            Series {
+                name: "write",
+                history: &app.disk_write_history,
+                color: colors.disk_write.0,
+            },
+        ],
+        peak,
+        format::rate(peak, app.rate_unit),
+        app.window,
+    );
+}
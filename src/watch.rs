@@ -0,0 +1,86 @@
+use crate::{
+    cli::{WatchArgs, WatchFormat},
+    collector,
+    config::Config,
+    format, metrics,
+    planner::Source,
+};
+use serde::{Serialize, Serializer, ser::SerializeMap};
+use std::{
+    error::Error,
+    io::{self, Write},
+};
+use tokio::sync::watch;
+
+struct Record<'a> {
+    timestamp: u64,
+    values: &'a [(String, f64)],
+}
+
+impl Serialize for Record<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(self.values.len() + 1))?;
+        map.serialize_entry("timestamp", &self.timestamp)?;
+        for (name, value) in self.values {
+            map.serialize_entry(name, &value.is_finite().then_some(value))?;
+        }
+        map.end()
+    }
+}
+
+fn write(out: &mut impl Write, args: &WatchArgs, values: &[(String, f64)]) -> io::Result<()> {
+    let timestamp = format::now();
+    match args.format {
+        WatchFormat::Text => {
+            let mut line = format::clock(timestamp);
+            for (name, value) in values {
+                if value.is_finite() {
+                    line.push_str(&format!(" {}={:.2}", name, value));
+                } else {
+                    line.push_str(&format!(" {}=-", name));
+                }
+            }
+            writeln!(out, "{}", line)
+        }
+        WatchFormat::Ndjson => {
+            serde_json::to_writer(&mut *out, &Record { timestamp, values })?;
+            writeln!(out)
+        }
+    }
+}
+
+pub async fn run(config: Config, args: WatchArgs) -> Result<(), Box<dyn Error>> {
+    let fields: Vec<String> = args.metrics.iter().chain(&args.fields).cloned().collect();
+    let (_refresh, refresh_rx) = watch::channel(config.refresh.clone());
+    let (_demand, demand_rx) = watch::channel(Source::ALL.into_iter().collect());
+    let mut snapshots = collector::spawn(refresh_rx, demand_rx);
+
+    let mut first = true;
+    let mut written = 0;
+    while snapshots.changed().await.is_ok() {
+        let snapshot = snapshots.borrow_and_update().clone();
+        let values = metrics::flatten(&snapshot, &config.network);
+        if first {
+            if let Some(field) = fields
+                .iter()
+                .find(|field| !values.iter().any(|(name, _)| metrics::matches(name, field)))
+            {
+                return Err(format!("unknown metric `{}`", field).into());
+            }
+            first = false;
+            continue;
+        }
+
+        let values = metrics::select(values, &fields);
+        match write(&mut io::stdout().lock(), &args, &values) {
+            Ok(()) => {}
+            Err(err) if err.kind() == io::ErrorKind::BrokenPipe => return Ok(()),
+            Err(err) => return Err(err.into()),
+        }
+        written += 1;
+        if args.count.is_some_and(|count| written >= count) {
+            break;
+        }
+    }
+    Ok(())
+}